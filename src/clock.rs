@@ -0,0 +1,34 @@
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// The source of time and frame delays of a spinner. Spinners use
+/// [`SystemClock`] unless another clock is passed to
+/// `SpinnerBuilder::clock`, such as a `testing::ManualClock`.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> Instant;
+
+    /// Blocks the render thread until the next frame is due.
+    fn sleep(&self, duration: Duration);
+
+    /// Wakes threads sleeping on the clock early, because the spinner
+    /// stopped.
+    fn wake(&self) {}
+
+    /// Tells the clock that the render thread is about to block on
+    /// something else, such as a pause, instead of sleeping.
+    fn idle(&self) {}
+}
+
+/// The real time, with real sleeps.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+
+    fn sleep(&self, duration: Duration) {
+        thread::sleep(duration);
+    }
+}
@@ -1 +1,547 @@
+use std::{env, fs, io, path::Path, path::PathBuf, str::FromStr, sync::Arc};
 
+use colored::Color;
+use lazy_static::lazy_static;
+use serde::Deserialize;
+
+use crate::spinner::{
+    builder::validate_rate, message::UpdateMessage, state::SpinnerState, styles::StyleRef,
+};
+use crate::{
+    Alignment, RenderMode, SpinnerError, SpinnerResult, SpinnerStream, FAILURE_SYMBOL, INFO_SYMBOL,
+    SUCCESS_SYMBOL, WARNING_SYMBOL,
+};
+
+const ENV_PREFIX: &str = "SPINERGY_";
+
+lazy_static! {
+    /// The crate's own tests start from an empty config, so that they do not
+    /// depend on the environment they run in.
+    static ref GLOBAL: Arc<SpinnerConfig> = Arc::new(match cfg!(test) {
+        true => SpinnerConfig::default(),
+        false => SpinnerConfig::load_lenient(),
+    });
+}
+
+/// Symbols used by `succeed`, `fail`, `warn` and `info`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct Symbols {
+    pub success: Option<String>,
+    pub failure: Option<String>,
+    pub warning: Option<String>,
+    pub info: Option<String>,
+}
+
+impl Symbols {
+    pub fn success(&self) -> &str {
+        self.success.as_deref().unwrap_or(SUCCESS_SYMBOL)
+    }
+
+    pub fn failure(&self) -> &str {
+        self.failure.as_deref().unwrap_or(FAILURE_SYMBOL)
+    }
+
+    pub fn warning(&self) -> &str {
+        self.warning.as_deref().unwrap_or(WARNING_SYMBOL)
+    }
+
+    pub fn info(&self) -> &str {
+        self.info.as_deref().unwrap_or(INFO_SYMBOL)
+    }
+
+    fn merge(self, other: Self) -> Self {
+        Self {
+            success: other.success.or(self.success),
+            failure: other.failure.or(self.failure),
+            warning: other.warning.or(self.warning),
+            info: other.info.or(self.info),
+        }
+    }
+}
+
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawColors {
+    spinner: Option<String>,
+    message: Option<String>,
+    dots: Option<String>,
+}
+
+/// The file layout, also used for values read from the environment.
+#[derive(Debug, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+struct RawConfig {
+    style: Option<String>,
+    colors: RawColors,
+    fps: Option<f64>,
+    alignment: Option<String>,
+    stream: Option<String>,
+    render_mode: Option<String>,
+    symbols: Symbols,
+}
+
+/// Spinner defaults chosen by the end user rather than the application.
+///
+/// Values come from `$XDG_CONFIG_HOME/spinergy/config.toml` (or
+/// `~/.config/spinergy/config.toml`, or the file named by `SPINERGY_CONFIG`),
+/// overridden by `SPINERGY_*` environment variables. Anything the
+/// application sets on a spinner or its builder takes precedence over both,
+/// except the render mode and the style: those are the end user's call, so
+/// that e.g. `SPINERGY_RENDER_MODE=hidden` silences every spinner.
+///
+/// Code that must not depend on the end user's settings, such as tests,
+/// can pass its own config to `SpinnerBuilder::config` or
+/// `MultiSpinner::with_config`, or use `SpinnerBuilder::ignore_config`.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SpinnerConfig {
+    pub style: Option<String>,
+    pub style_color: Option<Color>,
+    pub message_color: Option<Color>,
+    pub dots_color: Option<Color>,
+    pub fps: Option<f64>,
+    pub alignment: Option<Alignment>,
+    pub stream: Option<String>,
+    pub render_mode: Option<RenderMode>,
+    pub symbols: Symbols,
+}
+
+impl SpinnerConfig {
+    /// The configuration every new spinner starts from, loaded once. Invalid
+    /// sources are skipped so that a broken config file never stops a
+    /// program; use [`SpinnerConfig::load`] to see the errors.
+    pub fn global() -> &'static SpinnerConfig {
+        &GLOBAL
+    }
+
+    pub(crate) fn shared() -> Arc<SpinnerConfig> {
+        GLOBAL.clone()
+    }
+
+    /// Loads the config file, if any, and applies the environment on top.
+    pub fn load() -> SpinnerResult<Self> {
+        let file = match Self::path() {
+            Some(path) => Self::from_file_if_exists(&path)?,
+            None => Self::default(),
+        };
+        Ok(file.merge(Self::from_env()?))
+    }
+
+    fn load_lenient() -> Self {
+        let file = Self::path()
+            .and_then(|path| Self::from_file_if_exists(&path).ok())
+            .unwrap_or_default();
+        file.merge(Self::from_env().unwrap_or_default())
+    }
+
+    /// The config file location, whether or not it exists.
+    pub fn path() -> Option<PathBuf> {
+        Self::path_with(|key| env::var_os(key).map(PathBuf::from))
+    }
+
+    fn path_with<E>(var: E) -> Option<PathBuf>
+    where
+        E: Fn(&str) -> Option<PathBuf>,
+    {
+        if let Some(path) = var("SPINERGY_CONFIG") {
+            return Some(path);
+        }
+        let config_home = var("XDG_CONFIG_HOME")
+            .filter(|path| path.is_absolute())
+            .or_else(|| var("HOME").map(|home| home.join(".config")))?;
+        Some(config_home.join("spinergy").join("config.toml"))
+    }
+
+    pub fn from_file(path: impl AsRef<Path>) -> SpinnerResult<Self> {
+        let path = path.as_ref();
+        let source = fs::read_to_string(path).map_err(|e| read_error(path, e))?;
+        Self::from_toml(&source)
+    }
+
+    fn from_file_if_exists(path: &Path) -> SpinnerResult<Self> {
+        match fs::read_to_string(path) {
+            Ok(source) => Self::from_toml(&source),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(e) => Err(read_error(path, e)),
+        }
+    }
+
+    pub fn from_toml(source: &str) -> SpinnerResult<Self> {
+        let raw: RawConfig = toml::from_str(source)
+            .map_err(|e| SpinnerError::config_caused_by("Invalid spinner config", e))?;
+        raw.parse()
+    }
+
+    /// Reads the `SPINERGY_*` variables: `STYLE`, `SPINNER_COLOR`,
+    /// `MESSAGE_COLOR`, `DOTS_COLOR`, `FPS`, `ALIGNMENT`, `STREAM`,
+    /// `RENDER_MODE`, `SUCCESS_SYMBOL`, `FAILURE_SYMBOL`, `WARNING_SYMBOL` and
+    /// `INFO_SYMBOL`. Empty variables are ignored.
+    pub fn from_env() -> SpinnerResult<Self> {
+        Self::from_env_with(|key| env::var(key).ok())
+    }
+
+    fn from_env_with<E>(var: E) -> SpinnerResult<Self>
+    where
+        E: Fn(&str) -> Option<String>,
+    {
+        let var = |name: &str| var(&format!("{}{}", ENV_PREFIX, name)).filter(|v| !v.is_empty());
+        let fps = match var("FPS") {
+            Some(fps) => Some(fps.trim().parse().map_err(|e| {
+                SpinnerError::config_caused_by(format!("Invalid {}FPS: {}", ENV_PREFIX, fps), e)
+            })?),
+            None => None,
+        };
+
+        RawConfig {
+            style: var("STYLE"),
+            colors: RawColors {
+                spinner: var("SPINNER_COLOR"),
+                message: var("MESSAGE_COLOR"),
+                dots: var("DOTS_COLOR"),
+            },
+            fps,
+            alignment: var("ALIGNMENT"),
+            stream: var("STREAM"),
+            render_mode: var("RENDER_MODE"),
+            symbols: Symbols {
+                success: var("SUCCESS_SYMBOL"),
+                failure: var("FAILURE_SYMBOL"),
+                warning: var("WARNING_SYMBOL"),
+                info: var("INFO_SYMBOL"),
+            },
+        }
+        .parse()
+    }
+
+    /// Combines two configurations; values set in `other` win.
+    pub fn merge(self, other: Self) -> Self {
+        Self {
+            style: other.style.or(self.style),
+            style_color: other.style_color.or(self.style_color),
+            message_color: other.message_color.or(self.message_color),
+            dots_color: other.dots_color.or(self.dots_color),
+            fps: other.fps.or(self.fps),
+            alignment: other.alignment.or(self.alignment),
+            stream: other.stream.or(self.stream),
+            render_mode: other.render_mode.or(self.render_mode),
+            symbols: self.symbols.merge(other.symbols),
+        }
+    }
+
+    /// Applies the defaults to a spinner that has not started yet. The style
+    /// is resolved here, so it may name a style registered after loading;
+    /// unknown styles are skipped.
+    pub(crate) fn apply(&self, state: &mut SpinnerState) {
+        if let (Some(style), Some(data)) = (&self.style, self.style_data()) {
+            let _ = state.apply(UpdateMessage::Style(StyleRef::from(style.as_str()), data));
+        }
+        if self.has_colors() {
+            let (style_color, text_color, dot_color) = state.colors();
+            let _ = state.apply(UpdateMessage::Colors(
+                self.style_color.or(style_color),
+                self.message_color.or(text_color),
+                self.dots_color.or(dot_color),
+            ));
+        }
+        if let Some(fps) = self.fps {
+            let _ = state.apply(UpdateMessage::FramesPerSecond(fps));
+        }
+        if let Some(alignment) = self.alignment {
+            let _ = state.apply(UpdateMessage::Alignment(alignment));
+        }
+        if let Some(stream) = self.output_stream() {
+            let _ = state.apply(UpdateMessage::Stream(stream));
+        }
+        if let Some(render_mode) = self.render_mode {
+            let _ = state.apply(UpdateMessage::RenderMode(render_mode));
+        }
+    }
+
+    /// The configured render mode if there is one, otherwise `render_mode`.
+    pub(crate) fn render_mode_or(&self, render_mode: RenderMode) -> RenderMode {
+        self.render_mode.unwrap_or(render_mode)
+    }
+
+    /// The configured style if there is one and it resolves, otherwise the
+    /// given one.
+    pub(crate) fn style_or(
+        &self,
+        style: StyleRef,
+        data: crate::SpinnerData,
+    ) -> (StyleRef, crate::SpinnerData) {
+        match (&self.style, self.style_data()) {
+            (Some(name), Some(configured)) => (StyleRef::from(name.as_str()), configured),
+            _ => (style, data),
+        }
+    }
+
+    pub(crate) fn style_data(&self) -> Option<crate::SpinnerData> {
+        let style = self.style.as_deref()?;
+        StyleRef::from(style).resolve().ok()
+    }
+
+    pub(crate) fn has_colors(&self) -> bool {
+        self.style_color.is_some() || self.message_color.is_some() || self.dots_color.is_some()
+    }
+
+    pub(crate) fn output_stream(&self) -> Option<SpinnerStream> {
+        SpinnerStream::try_from(self.stream.as_deref()?).ok()
+    }
+}
+
+impl RawConfig {
+    fn parse(self) -> SpinnerResult<SpinnerConfig> {
+        if let Some(fps) = self.fps {
+            validate_rate("fps", fps)?;
+        }
+        if let Some(stream) = &self.stream {
+            SpinnerStream::try_from(stream.as_str())?;
+        }
+
+        Ok(SpinnerConfig {
+            style: self.style,
+            style_color: parse_color(self.colors.spinner)?,
+            message_color: parse_color(self.colors.message)?,
+            dots_color: parse_color(self.colors.dots)?,
+            fps: self.fps,
+            alignment: parse_named("alignment", self.alignment)?,
+            stream: self.stream,
+            render_mode: parse_named("render mode", self.render_mode)?,
+            symbols: self.symbols,
+        })
+    }
+}
+
+fn read_error(path: &Path, error: io::Error) -> SpinnerError {
+    let message = format!("Failed to read spinner config from {}", path.display());
+    SpinnerError::config_caused_by(message, error)
+}
+
+fn parse_color(value: Option<String>) -> SpinnerResult<Option<Color>> {
+    match value {
+        Some(value) => Color::from_str(&value.replace('_', " "))
+            .map(Some)
+            .map_err(|_| {
+                SpinnerError::config(format!("Invalid color in spinner config: {}", value))
+            }),
+        None => Ok(None),
+    }
+}
+
+/// `Alignment` and `RenderMode` fall back to a default for unknown names, so
+/// a value is only accepted if it converts back to the same name.
+fn parse_named<T>(name: &str, value: Option<String>) -> SpinnerResult<Option<T>>
+where
+    T: for<'a> From<&'a str> + ToString,
+{
+    match value {
+        Some(value) => {
+            let parsed = T::from(value.trim());
+            match parsed.to_string() == value.trim().to_lowercase() {
+                true => Ok(Some(parsed)),
+                false => Err(SpinnerError::config(format!(
+                    "Invalid {} in spinner config: {}",
+                    name, value
+                ))),
+            }
+        }
+        None => Ok(None),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, time::Duration};
+
+    use super::*;
+
+    fn env_of(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        move |key| vars.get(key).cloned()
+    }
+
+    #[test]
+    fn test_from_toml() {
+        let config = SpinnerConfig::from_toml(
+            r#"
+            style = "dots"
+            fps = 12.5
+            alignment = "Right"
+            stream = "stdout"
+            render_mode = "plain"
+
+            [colors]
+            spinner = "bright_cyan"
+            dots = "white"
+
+            [symbols]
+            success = "ok"
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(config.style.as_deref(), Some("dots"));
+        assert_eq!(config.fps, Some(12.5));
+        assert_eq!(config.alignment, Some(Alignment::Right));
+        assert_eq!(config.stream.as_deref(), Some("stdout"));
+        assert_eq!(config.render_mode, Some(RenderMode::Plain));
+        assert_eq!(config.style_color, Some(Color::BrightCyan));
+        assert_eq!(config.message_color, None);
+        assert_eq!(config.dots_color, Some(Color::White));
+        assert_eq!(config.symbols.success(), "ok");
+        assert_eq!(config.symbols.failure(), FAILURE_SYMBOL);
+    }
+
+    #[test]
+    fn test_from_toml_rejects_invalid_values() {
+        assert!(SpinnerConfig::from_toml("fps = 0").is_err());
+        assert!(SpinnerConfig::from_toml(r#"alignment = "middle""#).is_err());
+        assert!(SpinnerConfig::from_toml(r#"render_mode = "fancy""#).is_err());
+        assert!(SpinnerConfig::from_toml(r#"stream = "nowhere""#).is_err());
+        assert!(SpinnerConfig::from_toml("[colors]\nspinner = \"sparkly\"").is_err());
+        assert!(SpinnerConfig::from_toml("unknown = 1").is_err());
+    }
+
+    #[test]
+    fn test_errors_keep_their_source() {
+        let error = SpinnerConfig::from_toml("unknown = 1").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid spinner config");
+        assert!(std::error::Error::source(&error).is_some());
+
+        let error = SpinnerConfig::from_file("/nonexistent/spinergy.toml").unwrap_err();
+        assert!(error.to_string().ends_with("/nonexistent/spinergy.toml"));
+        assert!(std::error::Error::source(&error).is_some());
+    }
+
+    #[test]
+    fn test_from_env() {
+        let config = SpinnerConfig::from_env_with(env_of(&[
+            ("SPINERGY_STYLE", "line"),
+            ("SPINERGY_FPS", "5"),
+            ("SPINERGY_RENDER_MODE", "hidden"),
+            ("SPINERGY_MESSAGE_COLOR", "cyan"),
+            ("SPINERGY_INFO_SYMBOL", "i"),
+            ("SPINERGY_ALIGNMENT", ""),
+        ]))
+        .unwrap();
+
+        assert_eq!(config.style.as_deref(), Some("line"));
+        assert_eq!(config.fps, Some(5.0));
+        assert_eq!(config.render_mode, Some(RenderMode::Hidden));
+        assert_eq!(config.message_color, Some(Color::Cyan));
+        assert_eq!(config.symbols.info(), "i");
+        assert_eq!(config.alignment, None);
+    }
+
+    #[test]
+    fn test_from_env_rejects_invalid_fps() {
+        let result = SpinnerConfig::from_env_with(env_of(&[("SPINERGY_FPS", "fast")]));
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Invalid SPINERGY_FPS: fast"
+        );
+    }
+
+    #[test]
+    fn test_env_overrides_file() {
+        let file = SpinnerConfig::from_toml(
+            "style = \"dots\"\nfps = 10.0\n[symbols]\nsuccess = \"+\"\nfailure = \"-\"",
+        )
+        .unwrap();
+        let env = SpinnerConfig::from_env_with(env_of(&[
+            ("SPINERGY_STYLE", "line"),
+            ("SPINERGY_FAILURE_SYMBOL", "x"),
+        ]))
+        .unwrap();
+        let config = file.merge(env);
+
+        assert_eq!(config.style.as_deref(), Some("line"));
+        assert_eq!(config.fps, Some(10.0));
+        assert_eq!(config.symbols.success(), "+");
+        assert_eq!(config.symbols.failure(), "x");
+    }
+
+    #[test]
+    fn test_path() {
+        let path = SpinnerConfig::path_with(|key| match key {
+            "XDG_CONFIG_HOME" => Some(PathBuf::from("/xdg")),
+            "HOME" => Some(PathBuf::from("/home/user")),
+            _ => None,
+        });
+        assert_eq!(path, Some(PathBuf::from("/xdg/spinergy/config.toml")));
+
+        let path = SpinnerConfig::path_with(|key| match key {
+            "XDG_CONFIG_HOME" => Some(PathBuf::from("relative")),
+            "HOME" => Some(PathBuf::from("/home/user")),
+            _ => None,
+        });
+        assert_eq!(
+            path,
+            Some(PathBuf::from("/home/user/.config/spinergy/config.toml"))
+        );
+
+        let path = SpinnerConfig::path_with(|key| match key {
+            "SPINERGY_CONFIG" => Some(PathBuf::from("/etc/spinergy.toml")),
+            _ => Some(PathBuf::from("/ignored")),
+        });
+        assert_eq!(path, Some(PathBuf::from("/etc/spinergy.toml")));
+    }
+
+    #[test]
+    fn test_missing_file_is_empty() {
+        let config =
+            SpinnerConfig::from_file_if_exists(Path::new("/nonexistent/spinergy/config.toml"));
+        assert_eq!(config.unwrap(), SpinnerConfig::default());
+        assert!(SpinnerConfig::from_file("/nonexistent/spinergy/config.toml").is_err());
+    }
+
+    #[test]
+    fn test_apply_sets_state_defaults() {
+        let config = SpinnerConfig {
+            style: Some("line".to_string()),
+            message_color: Some(Color::Cyan),
+            ..SpinnerConfig::default()
+        };
+        let mut state = SpinnerState::new("Loading");
+        config.apply(&mut state);
+        assert_eq!(
+            state.colors(),
+            (
+                Some(Color::Magenta),
+                Some(Color::Cyan),
+                Some(Color::Magenta)
+            )
+        );
+        assert_eq!(state.frame_interval(), Duration::from_millis(130));
+    }
+
+    #[test]
+    fn test_render_mode_and_style_override_the_application() {
+        let dots = StyleRef::from("dots");
+        let dots_data = dots.resolve().unwrap();
+
+        let config = SpinnerConfig::default();
+        assert_eq!(config.render_mode_or(RenderMode::Plain), RenderMode::Plain);
+        let (_, data) = config.style_or(dots.clone(), dots_data.clone());
+        assert_eq!(data, dots_data);
+
+        let config = SpinnerConfig {
+            style: Some("line".to_string()),
+            render_mode: Some(RenderMode::Hidden),
+            ..SpinnerConfig::default()
+        };
+        assert_eq!(config.render_mode_or(RenderMode::Plain), RenderMode::Hidden);
+        let (_, data) = config.style_or(dots.clone(), dots_data.clone());
+        assert_eq!(data, StyleRef::from("line").resolve().unwrap());
+
+        let config = SpinnerConfig {
+            style: Some("not-a-style".to_string()),
+            ..SpinnerConfig::default()
+        };
+        let (_, data) = config.style_or(dots, dots_data.clone());
+        assert_eq!(data, dots_data);
+    }
+}
@@ -1,30 +1,154 @@
+use std::convert::Infallible;
+use std::error::Error;
 use std::fmt;
+use std::io;
 
-#[derive(Debug, Clone)]
-pub struct SpinnerError {
-    message: String,
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum SpinnerError {
+    AlreadyRunning,
+    NotRunning,
+    AlreadyPaused,
+    NotPaused,
+    /// The render thread or task is gone and no longer takes updates.
+    ChannelClosed,
+    ThreadPanicked,
+    /// The child spinner was removed from its `MultiSpinner`.
+    Removed,
+    Io(io::Error),
+    UnknownStyle {
+        name: String,
+        suggestions: Vec<String>,
+    },
+    InvalidFrames,
+    InvalidRate {
+        name: String,
+        value: f64,
+    },
+    InvalidTemplate(String),
+    InvalidStream(String),
+    /// A config, style file or environment variable could not be used.
+    Config {
+        message: String,
+        source: Option<Box<dyn Error + Send + Sync>>,
+    },
+    /// The async runtime or the logging framework is unavailable.
+    Runtime(String),
 }
 
 impl SpinnerError {
-    pub fn new(message: &str) -> Self {
-        Self {
-            message: message.to_owned(),
+    pub(crate) fn config(message: impl Into<String>) -> Self {
+        SpinnerError::Config {
+            message: message.into(),
+            source: None,
+        }
+    }
+
+    pub(crate) fn config_caused_by<E>(message: impl Into<String>, source: E) -> Self
+    where
+        E: Into<Box<dyn Error + Send + Sync>>,
+    {
+        SpinnerError::Config {
+            message: message.into(),
+            source: Some(source.into()),
         }
     }
 }
 
-impl From<&str> for SpinnerError {
-    fn from(error_message: &str) -> Self {
-        SpinnerError::new(error_message)
+impl From<io::Error> for SpinnerError {
+    fn from(error: io::Error) -> Self {
+        SpinnerError::Io(error)
+    }
+}
+
+impl From<Infallible> for SpinnerError {
+    fn from(never: Infallible) -> Self {
+        match never {}
     }
 }
 
 impl fmt::Display for SpinnerError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(f, "{}", self.message)
+        match self {
+            SpinnerError::AlreadyRunning => write!(f, "Spinner is already running"),
+            SpinnerError::NotRunning => write!(f, "Spinner is not running"),
+            SpinnerError::AlreadyPaused => write!(f, "Spinner is already paused"),
+            SpinnerError::NotPaused => write!(f, "Spinner is not paused"),
+            SpinnerError::ChannelClosed => write!(f, "Spinner channel is closed"),
+            SpinnerError::ThreadPanicked => write!(f, "Spinner thread panicked"),
+            SpinnerError::Removed => write!(f, "Spinner has been removed"),
+            SpinnerError::Io(_) => write!(f, "Spinner output failed"),
+            SpinnerError::UnknownStyle { name, suggestions } => match suggestions.as_slice() {
+                [] => write!(f, "Unknown spinner style: {}", name),
+                names => write!(
+                    f,
+                    "Unknown spinner style: {}. Did you mean {}?",
+                    name,
+                    names.join(", ")
+                ),
+            },
+            SpinnerError::InvalidFrames => write!(f, "Spinner frames must not be empty"),
+            SpinnerError::InvalidRate { name, value } => {
+                write!(f, "Invalid spinner {}: {}", name, value)
+            }
+            SpinnerError::InvalidTemplate(message) => write!(f, "{}", message),
+            SpinnerError::InvalidStream(name) => write!(f, "Invalid spinner stream: {}", name),
+            SpinnerError::Config { message, .. } => write!(f, "{}", message),
+            SpinnerError::Runtime(message) => write!(f, "{}", message),
+        }
     }
 }
 
-impl std::error::Error for SpinnerError {}
+impl Error for SpinnerError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            SpinnerError::Io(error) => Some(error),
+            SpinnerError::Config {
+                source: Some(source),
+                ..
+            } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
 
 pub type SpinnerResult<T> = std::result::Result<T, SpinnerError>;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        assert_eq!(
+            SpinnerError::AlreadyRunning.to_string(),
+            "Spinner is already running"
+        );
+        let error = SpinnerError::UnknownStyle {
+            name: "dotz".to_string(),
+            suggestions: vec!["Dots".to_string(), "Dots2".to_string()],
+        };
+        assert_eq!(
+            error.to_string(),
+            "Unknown spinner style: dotz. Did you mean Dots, Dots2?"
+        );
+    }
+
+    #[test]
+    fn test_io_source() {
+        let error = SpinnerError::from(io::Error::new(io::ErrorKind::BrokenPipe, "closed"));
+        assert!(matches!(error, SpinnerError::Io(_)));
+        assert_eq!(error.to_string(), "Spinner output failed");
+        assert_eq!(error.source().unwrap().to_string(), "closed");
+        assert!(SpinnerError::NotRunning.source().is_none());
+    }
+
+    #[test]
+    fn test_config_source() {
+        let cause = io::Error::new(io::ErrorKind::PermissionDenied, "denied");
+        let error = SpinnerError::config_caused_by("Failed to read spinner config", cause);
+        assert_eq!(error.to_string(), "Failed to read spinner config");
+        assert_eq!(error.source().unwrap().to_string(), "denied");
+        assert!(SpinnerError::config("Invalid color").source().is_none());
+    }
+}
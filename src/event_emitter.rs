@@ -1,41 +1,211 @@
 use std::{
-    collections::HashMap,
-    sync::{Arc, Mutex},
+    cell::RefCell,
+    collections::{HashMap, HashSet},
+    sync::{
+        atomic::{AtomicBool, AtomicUsize, Ordering},
+        Arc, Mutex, Weak,
+    },
 };
 
-pub type EventCallback = Box<dyn FnMut(&[Box<dyn std::any::Any>]) + Send + Sync>;
-pub type EventCallbackList = Vec<EventCallback>;
-pub type EventCallbackMap = HashMap<String, EventCallbackList>;
+use crossbeam::channel::{bounded, unbounded, Receiver, Sender, TrySendError};
+
+use crate::{Event, SpinnerEvent};
+
+pub type EventCallback = Box<dyn FnMut(&SpinnerEvent) + Send>;
+pub type EventCallbackList = Vec<Arc<Listener>>;
+pub type EventCallbackMap = HashMap<Event, EventCallbackList>;
 pub type SharedEventCallbackMap = Arc<Mutex<EventCallbackMap>>;
 
+static NEXT_LISTENER_ID: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    /// Listeners currently running on this thread, so that an event they
+    /// trigger themselves does not call them again.
+    static DISPATCHING: RefCell<HashSet<usize>> = RefCell::new(HashSet::new());
+}
+
+pub struct Listener {
+    id: usize,
+    once: bool,
+    active: AtomicBool,
+    callback: Mutex<EventCallback>,
+}
+
+impl Listener {
+    fn call(&self, event: &SpinnerEvent) {
+        if !self.active.load(Ordering::SeqCst) {
+            return;
+        }
+        let entered = DISPATCHING.with(|running| running.borrow_mut().insert(self.id));
+        if !entered {
+            return;
+        }
+        let _dispatching = Dispatching(self.id);
+        if let Ok(mut callback) = self.callback.lock() {
+            callback(event);
+        }
+    }
+}
+
+/// The listeners running on this thread.
+pub fn dispatching() -> Vec<usize> {
+    DISPATCHING.with(|running| running.borrow().iter().copied().collect())
+}
+
+/// Runs `f` as if the given listeners were running, so that the events it
+/// emits skip them. Used to apply updates that listeners queued themselves.
+pub fn dispatching_as<R>(listeners: &[usize], f: impl FnOnce() -> R) -> R {
+    let _dispatching: Vec<Dispatching> = listeners
+        .iter()
+        .filter(|&&id| DISPATCHING.with(|running| running.borrow_mut().insert(id)))
+        .map(|&id| Dispatching(id))
+        .collect();
+    f()
+}
+
+/// Unmarks a listener as running, even if it panics.
+struct Dispatching(usize);
+
+impl Drop for Dispatching {
+    fn drop(&mut self) {
+        DISPATCHING.with(|running| running.borrow_mut().remove(&self.0));
+    }
+}
+
+/// Keeps a listener registered. The listener is removed when the
+/// subscription is dropped, unless it is detached first.
+#[must_use = "the listener is removed as soon as the subscription is dropped"]
+pub struct Subscription {
+    id: usize,
+    event: Event,
+    listeners: Weak<Mutex<EventCallbackMap>>,
+}
+
+impl Subscription {
+    /// Keeps the listener for as long as the spinner lives.
+    pub fn detach(mut self) {
+        self.listeners = Weak::new();
+    }
+
+    pub fn unsubscribe(self) {}
+}
+
+impl Drop for Subscription {
+    fn drop(&mut self) {
+        let Some(listeners) = self.listeners.upgrade() else {
+            return;
+        };
+        let mut listeners = listeners.lock().unwrap();
+        if let Some(list) = listeners.get_mut(&self.event) {
+            list.retain(|listener| {
+                let keep = listener.id != self.id;
+                if !keep {
+                    listener.active.store(false, Ordering::SeqCst);
+                }
+                keep
+            });
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct EventEmitter {
     listeners: SharedEventCallbackMap,
+    subscribers: Arc<Mutex<Vec<Sender<SpinnerEvent>>>>,
 }
 
 impl EventEmitter {
     pub fn new() -> Self {
         let listeners = Arc::new(Mutex::new(HashMap::new()));
-        Self { listeners }
+        let subscribers = Arc::new(Mutex::new(Vec::new()));
+        Self {
+            listeners,
+            subscribers,
+        }
+    }
+
+    /// Returns a receiver of every event emitted from now on. The channel is
+    /// closed once the receiver is dropped.
+    pub fn subscribe(&self) -> Receiver<SpinnerEvent> {
+        let (sender, receiver) = unbounded();
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
     }
 
-    pub fn on<F>(&mut self, event_name: &str, listener: F)
+    /// Like `subscribe`, but holds at most `capacity` unread events; newer
+    /// events are dropped for this receiver while it is full.
+    pub fn subscribe_bounded(&self, capacity: usize) -> Receiver<SpinnerEvent> {
+        let (sender, receiver) = bounded(capacity);
+        self.subscribers.lock().unwrap().push(sender);
+        receiver
+    }
+
+    /// Registers a listener. While it runs, events emitted on the same
+    /// thread are skipped for it instead of calling it again, and so are the
+    /// events caused by spinner updates it queues, so a listener that
+    /// triggers its own event does not recurse.
+    pub fn on<F>(&mut self, event: Event, listener: F) -> Subscription
     where
-        F: FnMut(&[Box<dyn std::any::Any>]) + 'static + Sync + Send,
+        F: FnMut(&SpinnerEvent) + Send + 'static,
     {
-        let mut listeners = self.listeners.lock().unwrap();
-        listeners
-            .entry(event_name.to_string())
-            .or_insert(Vec::new())
-            .push(Box::new(listener));
+        self.register(event, false, Box::new(listener))
     }
 
-    pub fn emit(&mut self, event_name: &str, args: &[Box<dyn std::any::Any>]) {
-        let mut listeners = self.listeners.lock().unwrap();
-        if let Some(listeners) = listeners.get_mut(event_name) {
-            for listener in listeners {
-                listener(args);
+    /// Like `on`, but the listener is removed after its first call. Events it
+    /// triggers while running are skipped for it as well.
+    pub fn once<F>(&mut self, event: Event, listener: F) -> Subscription
+    where
+        F: FnOnce(&SpinnerEvent) + Send + 'static,
+    {
+        let mut listener = Some(listener);
+        let callback = move |event: &SpinnerEvent| {
+            if let Some(listener) = listener.take() {
+                listener(event);
             }
+        };
+        self.register(event, true, Box::new(callback))
+    }
+
+    fn register(&mut self, event: Event, once: bool, callback: EventCallback) -> Subscription {
+        let id = NEXT_LISTENER_ID.fetch_add(1, Ordering::Relaxed);
+        let listener = Arc::new(Listener {
+            id,
+            once,
+            active: AtomicBool::new(true),
+            callback: Mutex::new(callback),
+        });
+        let mut listeners = self.listeners.lock().unwrap();
+        listeners.entry(event).or_default().push(listener);
+        Subscription {
+            id,
+            event,
+            listeners: Arc::downgrade(&self.listeners),
+        }
+    }
+
+    /// Calls the listeners registered when the event is emitted. The lock on
+    /// the listeners is released first, so listeners may subscribe,
+    /// unsubscribe and emit events themselves.
+    pub fn emit(&self, event: &SpinnerEvent) {
+        self.subscribers
+            .lock()
+            .unwrap()
+            .retain(|sender| match sender.try_send(event.clone()) {
+                Err(TrySendError::Disconnected(_)) => false,
+                Ok(()) | Err(TrySendError::Full(_)) => true,
+            });
+
+        let snapshot = {
+            let mut listeners = self.listeners.lock().unwrap();
+            let Some(list) = listeners.get_mut(&event.kind()) else {
+                return;
+            };
+            let snapshot = list.clone();
+            list.retain(|listener| !listener.once);
+            snapshot
+        };
+        for listener in snapshot {
+            listener.call(event);
         }
     }
 }
@@ -43,44 +213,165 @@ impl EventEmitter {
 #[cfg(test)]
 mod tests {
     use std::sync::{Arc, Mutex};
+    use std::time::Duration;
 
     use super::*;
 
+    fn recorder() -> (Arc<Mutex<Vec<SpinnerEvent>>>, impl Fn(&SpinnerEvent)) {
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_clone = received.clone();
+        let listener = move |event: &SpinnerEvent| {
+            received_clone.lock().unwrap().push(event.clone());
+        };
+        (received, listener)
+    }
+
     #[test]
     fn test_event_emitter() {
-        // Create a new EventEmitter
         let mut emitter = EventEmitter::new();
+        let (received, listener) = recorder();
+        let _subscription = emitter.on(Event::Stop, listener);
 
-        // Define a counter for tracking the number of times the event is emitted
-        let counter = Arc::new(Mutex::new(0));
-        let counter_clone = counter.clone();
-
-        // Define a listener function
-        let listener = move |args: &[Box<dyn std::any::Any>]| {
-            *counter_clone.lock().unwrap() += 1;
-            println!("Event emitted with arguments:");
-            for arg in args {
-                if let Some(value) = arg.downcast_ref::<i32>() {
-                    println!("i32: {}", value);
-                } else if let Some(value) = arg.downcast_ref::<&str>() {
-                    println!("&str: {}", value);
-                } else if let Some(value) = arg.downcast_ref::<f64>() {
-                    println!("f64: {}", value);
-                } else {
-                    println!("Unknown type");
-                }
+        let stop = SpinnerEvent::Stop {
+            elapsed: Duration::from_secs(2),
+        };
+        emitter.emit(&SpinnerEvent::Start);
+        emitter.emit(&stop);
+
+        assert_eq!(*received.lock().unwrap(), vec![stop]);
+    }
+
+    #[test]
+    fn test_drop_subscription_removes_listener() {
+        let mut emitter = EventEmitter::new();
+        let (received, listener) = recorder();
+        let subscription = emitter.on(Event::Start, listener);
+
+        emitter.emit(&SpinnerEvent::Start);
+        drop(subscription);
+        emitter.emit(&SpinnerEvent::Start);
+
+        assert_eq!(received.lock().unwrap().len(), 1);
+        assert!(emitter.listeners.lock().unwrap()[&Event::Start].is_empty());
+    }
+
+    #[test]
+    fn test_detached_subscription_keeps_listener() {
+        let mut emitter = EventEmitter::new();
+        let (received, listener) = recorder();
+        emitter.on(Event::Start, listener).detach();
+
+        emitter.emit(&SpinnerEvent::Start);
+        emitter.emit(&SpinnerEvent::Start);
+
+        assert_eq!(received.lock().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_once() {
+        let mut emitter = EventEmitter::new();
+        let (received, listener) = recorder();
+        let _subscription = emitter.once(Event::Start, listener);
+
+        emitter.emit(&SpinnerEvent::Start);
+        emitter.emit(&SpinnerEvent::Start);
+
+        assert_eq!(received.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_listener_need_not_be_sync() {
+        let mut emitter = EventEmitter::new();
+        let calls = std::cell::Cell::new(0);
+        let (sender, receiver) = std::sync::mpsc::channel();
+        let _subscription = emitter.on(Event::Start, move |_| {
+            calls.set(calls.get() + 1);
+            sender.send(calls.get()).unwrap();
+        });
+
+        emitter.emit(&SpinnerEvent::Start);
+        emitter.emit(&SpinnerEvent::Start);
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![1, 2]);
+    }
+
+    #[test]
+    fn test_listener_can_subscribe_and_emit() {
+        let mut emitter = EventEmitter::new();
+        let (received, listener) = recorder();
+        let listener = Arc::new(Mutex::new(Some(listener)));
+        let mut inner = emitter.clone();
+        let subscriptions = Arc::new(Mutex::new(Vec::new()));
+        let subscriptions_clone = subscriptions.clone();
+
+        let _subscription = emitter.on(Event::Start, move |_| {
+            if let Some(listener) = listener.lock().unwrap().take() {
+                let subscription = inner.on(Event::Stop, listener);
+                subscriptions_clone.lock().unwrap().push(subscription);
             }
+            // Re-entering the same event does not call this listener again.
+            inner.emit(&SpinnerEvent::Start);
+            inner.emit(&SpinnerEvent::Stop {
+                elapsed: Duration::ZERO,
+            });
+        });
+
+        emitter.emit(&SpinnerEvent::Start);
+
+        assert_eq!(received.lock().unwrap().len(), 1);
+        assert_eq!(subscriptions.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_subscribe() {
+        let emitter = EventEmitter::new();
+        emitter.emit(&SpinnerEvent::Start);
+        let receiver = emitter.subscribe();
+        let stop = SpinnerEvent::Stop {
+            elapsed: Duration::ZERO,
         };
+        emitter.emit(&stop);
+
+        assert_eq!(receiver.try_iter().collect::<Vec<_>>(), vec![stop]);
+
+        drop(receiver);
+        emitter.emit(&SpinnerEvent::Start);
+        assert!(emitter.subscribers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_subscribe_bounded_drops_events_when_full() {
+        let emitter = EventEmitter::new();
+        let receiver = emitter.subscribe_bounded(2);
+        for _ in 0..5 {
+            emitter.emit(&SpinnerEvent::Start);
+        }
+        assert_eq!(receiver.try_iter().count(), 2);
+
+        emitter.emit(&SpinnerEvent::Start);
+        assert_eq!(receiver.try_iter().count(), 1);
+
+        drop(receiver);
+        emitter.emit(&SpinnerEvent::Start);
+        assert!(emitter.subscribers.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_listener_can_unsubscribe_itself() {
+        let mut emitter = EventEmitter::new();
+        let slot: Arc<Mutex<Option<Subscription>>> = Arc::new(Mutex::new(None));
+        let slot_clone = slot.clone();
+        let calls = Arc::new(AtomicUsize::new(0));
+        let calls_clone = calls.clone();
 
-        // Register the listener for the "my_event" event
-        emitter.on("my_event", listener);
+        let subscription = emitter.on(Event::Start, move |_| {
+            calls_clone.fetch_add(1, Ordering::SeqCst);
+            slot_clone.lock().unwrap().take();
+        });
+        *slot.lock().unwrap() = Some(subscription);
 
-        // Emit the "my_event" event with some arguments
-        let args: Vec<Box<dyn std::any::Any>> =
-            vec![Box::new(42), Box::new("Hello"), Box::new(3.14)];
-        emitter.emit("my_event", &args);
+        emitter.emit(&SpinnerEvent::Start);
+        emitter.emit(&SpinnerEvent::Start);
 
-        // Verify that the listener was invoked and the counter was incremented
-        assert_eq!(*counter.lock().unwrap(), 1);
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
     }
 }
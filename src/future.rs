@@ -0,0 +1,161 @@
+use std::fmt::Display;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+use crate::spinner::state::trim_trailing_dots;
+use crate::Spinner;
+
+/// Shows a spinner while a fallible future runs.
+pub trait SpinnerFutureExt: Future + Sized {
+    fn with_spinner<M>(self, message: M) -> WithSpinner<Self>
+    where
+        M: Into<String>;
+}
+
+impl<F, T, E> SpinnerFutureExt for F
+where
+    F: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    fn with_spinner<M>(self, message: M) -> WithSpinner<Self>
+    where
+        M: Into<String>,
+    {
+        let message = message.into();
+        let (success_message, _) = trim_trailing_dots(message.clone());
+        WithSpinner {
+            future: Box::pin(self),
+            spinner: Spinner::new(message),
+            success_message,
+            started: false,
+        }
+    }
+}
+
+/// Starts the spinner when first polled and finishes it with `succeed` or
+/// `fail` once the wrapped future resolves. A failure shows the error.
+pub struct WithSpinner<F> {
+    future: Pin<Box<F>>,
+    spinner: Spinner,
+    success_message: String,
+    started: bool,
+}
+
+impl<F> WithSpinner<F> {
+    pub fn success_message<T>(mut self, message: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.success_message = message.into();
+        self
+    }
+
+    /// Gives access to the spinner to configure it before the future is
+    /// awaited.
+    pub fn spinner_mut(&mut self) -> &mut Spinner {
+        &mut self.spinner
+    }
+}
+
+impl<F, T, E> Future for WithSpinner<F>
+where
+    F: Future<Output = Result<T, E>>,
+    E: Display,
+{
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        if !this.started {
+            this.started = true;
+            // Outside a tokio runtime, fall back to a render thread.
+            if this.spinner.start_async().is_err() {
+                let _ = this.spinner.start();
+            }
+        }
+
+        let output = match this.future.as_mut().poll(cx) {
+            Poll::Ready(output) => output,
+            Poll::Pending => return Poll::Pending,
+        };
+        let _ = match &output {
+            Ok(_) => this.spinner.succeed(&this.success_message),
+            Err(error) => this.spinner.fail(error.to_string()),
+        };
+        Poll::Ready(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::RenderMode;
+
+    async fn fetch(ok: bool) -> Result<u32, String> {
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        match ok {
+            true => Ok(42),
+            false => Err("connection refused".to_string()),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_with_spinner_passes_output_through() {
+        let mut future = fetch(true).with_spinner("Fetching...");
+        future
+            .spinner_mut()
+            .set_render_mode(RenderMode::Hidden)
+            .unwrap();
+        assert_eq!(future.await, Ok(42));
+
+        let mut future = fetch(false).with_spinner("Fetching...");
+        future
+            .spinner_mut()
+            .set_render_mode(RenderMode::Hidden)
+            .unwrap();
+        assert_eq!(future.await, Err("connection refused".to_string()));
+    }
+
+    #[tokio::test]
+    async fn test_with_spinner_finishes_spinner() {
+        let finished = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+        let recorded = finished.clone();
+        let mut future = fetch(false)
+            .with_spinner("Fetching...")
+            .success_message("Fetched");
+        future
+            .spinner_mut()
+            .set_render_mode(RenderMode::Hidden)
+            .unwrap();
+        future.spinner_mut().on_finish(move |symbol, text| {
+            recorded
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", symbol, text));
+        });
+        let _ = future.await;
+
+        assert_eq!(*finished.lock().unwrap(), vec!["✖ connection refused"]);
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_with_spinner_is_send() {
+        let future = fetch(true)
+            .with_spinner("Fetching...")
+            .success_message("Fetched");
+        assert_eq!(tokio::spawn(future).await.unwrap(), Ok(42));
+    }
+
+    #[test]
+    fn test_with_spinner_on_current_thread_runtime() {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_time()
+            .build()
+            .unwrap();
+        let future = async { Ok::<_, String>(1) }.with_spinner("Working");
+        assert_eq!(runtime.block_on(future), Ok(1));
+    }
+}
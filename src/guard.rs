@@ -0,0 +1,220 @@
+use std::panic;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use crate::{spinner::registry::ACTIVE_SPINNERS, SpinnerResult};
+
+/// Restores the terminal when the program panics or receives SIGINT/SIGTERM
+/// while spinners are running.
+///
+/// Installing the guard wraps the current panic hook and, on unix, listens
+/// for SIGINT and SIGTERM. Active spinner lines are cleared, the cursor is
+/// shown again and an optional "interrupted" final line is printed before
+/// the previous panic hook runs. Signal handlers installed earlier are still
+/// called; by default the signal's default action (terminating the process)
+/// follows, which can be turned off with `exit_on_signal(false)` for
+/// programs that handle shutdown themselves. Once the guard is dropped,
+/// signals take their default action again.
+pub struct TerminalGuard {
+    interrupted_message: Option<String>,
+    exit_on_signal: bool,
+}
+
+impl TerminalGuard {
+    pub fn new() -> Self {
+        Self {
+            interrupted_message: None,
+            exit_on_signal: true,
+        }
+    }
+
+    pub fn interrupted_message<T>(mut self, message: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.interrupted_message = Some(message.into());
+        self
+    }
+
+    pub fn exit_on_signal(mut self, exit_on_signal: bool) -> Self {
+        self.exit_on_signal = exit_on_signal;
+        self
+    }
+
+    pub fn install(self) -> SpinnerResult<InstalledGuard> {
+        let enabled = Arc::new(AtomicBool::new(true));
+        let message = self.interrupted_message.map(Arc::<str>::from);
+
+        #[cfg(unix)]
+        signals::listen(signals::Listener {
+            enabled: enabled.clone(),
+            message: message.clone(),
+            exit_on_signal: self.exit_on_signal,
+        })?;
+
+        let previous = panic::take_hook();
+        let panic_enabled = enabled.clone();
+        panic::set_hook(Box::new(move |info| {
+            if panic_enabled.load(Ordering::SeqCst) {
+                ACTIVE_SPINNERS.interrupt_all(message.as_deref());
+            }
+            previous(info);
+        }));
+
+        Ok(InstalledGuard { enabled })
+    }
+}
+
+impl Default for TerminalGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Keeps the terminal guard active until dropped.
+#[must_use = "the guard is disabled as soon as it is dropped"]
+pub struct InstalledGuard {
+    enabled: Arc<AtomicBool>,
+}
+
+impl InstalledGuard {
+    pub fn is_enabled(&self) -> bool {
+        self.enabled.load(Ordering::SeqCst)
+    }
+}
+
+impl Drop for InstalledGuard {
+    fn drop(&mut self) {
+        // The panic hook stays installed but passes through, and signals go
+        // back to an earlier guard that is still installed, or else to their
+        // default action.
+        self.enabled.store(false, Ordering::SeqCst);
+    }
+}
+
+#[cfg(unix)]
+mod signals {
+    use std::sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    };
+    use std::thread;
+
+    use signal_hook::{
+        consts::{SIGINT, SIGTERM},
+        iterator::Signals,
+        low_level::emulate_default_handler,
+    };
+
+    use crate::{
+        spinner::registry::{lock, ACTIVE_SPINNERS},
+        SpinnerResult,
+    };
+
+    pub struct Listener {
+        pub enabled: Arc<AtomicBool>,
+        pub message: Option<Arc<str>>,
+        pub exit_on_signal: bool,
+    }
+
+    /// Every installed guard, latest last. Signal handlers cannot be removed
+    /// without leaving the signal ignored, so a single thread listens for the
+    /// life of the process and follows the latest guard that is still
+    /// installed.
+    static LISTENERS: Mutex<Vec<Listener>> = Mutex::new(Vec::new());
+    static LISTENING: Mutex<bool> = Mutex::new(false);
+
+    pub fn listen(listener: Listener) -> SpinnerResult<()> {
+        let mut listening = lock(&LISTENING);
+        if !*listening {
+            let mut signals = Signals::new([SIGINT, SIGTERM])?;
+            thread::spawn(move || {
+                for signal in signals.forever() {
+                    handle(signal);
+                }
+            });
+            *listening = true;
+        }
+        lock(&LISTENERS).push(listener);
+        Ok(())
+    }
+
+    /// Restores the terminal for the current guard. Without one, the signal
+    /// gets its default action, as if no guard had ever been installed.
+    fn handle(signal: i32) {
+        let exit = {
+            let mut listeners = lock(&LISTENERS);
+            match current(&mut listeners) {
+                Some(listener) => {
+                    ACTIVE_SPINNERS.interrupt_all(listener.message.as_deref());
+                    listener.exit_on_signal
+                }
+                None => true,
+            }
+        };
+        if exit {
+            let _ = emulate_default_handler(signal);
+        }
+    }
+
+    /// Drops the guards that were uninstalled and returns the latest one left.
+    pub fn current(listeners: &mut Vec<Listener>) -> Option<&Listener> {
+        listeners.retain(|listener| listener.enabled.load(Ordering::SeqCst));
+        listeners.last()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_terminal_guard_defaults() {
+        let guard = TerminalGuard::default();
+        assert_eq!(guard.interrupted_message, None);
+        assert!(guard.exit_on_signal);
+    }
+
+    #[test]
+    fn test_terminal_guard_options() {
+        let guard = TerminalGuard::new()
+            .interrupted_message("Interrupted")
+            .exit_on_signal(false);
+        assert_eq!(guard.interrupted_message.as_deref(), Some("Interrupted"));
+        assert!(!guard.exit_on_signal);
+    }
+
+    #[test]
+    fn test_installed_guard_disables_on_drop() {
+        let enabled = Arc::new(AtomicBool::new(true));
+        let guard = InstalledGuard {
+            enabled: enabled.clone(),
+        };
+        assert!(guard.is_enabled());
+        drop(guard);
+        assert!(!enabled.load(Ordering::SeqCst));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_signals_follow_latest_installed_guard() {
+        let listener = |message: &str| signals::Listener {
+            enabled: Arc::new(AtomicBool::new(true)),
+            message: Some(Arc::from(message)),
+            exit_on_signal: false,
+        };
+        let mut listeners = vec![listener("first"), listener("second")];
+        let current = signals::current(&mut listeners).unwrap();
+        assert_eq!(current.message.as_deref(), Some("second"));
+
+        listeners[1].enabled.store(false, Ordering::SeqCst);
+        let current = signals::current(&mut listeners).unwrap();
+        assert_eq!(current.message.as_deref(), Some("first"));
+
+        listeners[0].enabled.store(false, Ordering::SeqCst);
+        assert!(signals::current(&mut listeners).is_none());
+        assert!(listeners.is_empty());
+    }
+}
@@ -0,0 +1,280 @@
+use std::io::{self, BufRead, Read, Write};
+use std::mem;
+use std::time::{Duration, Instant};
+
+use crate::spinner::progress::ProgressUnit;
+use crate::Spinner;
+
+/// Bytes are reported to the spinner at most this often, instead of once per
+/// call, so that many small reads or writes do not flood its update queue.
+pub(crate) const REPORT_INTERVAL: Duration = Duration::from_millis(50);
+
+/// Counts the bytes read through it and reports them to a spinner, which
+/// shows the amount transferred and the throughput.
+pub struct SpinnerReader<R> {
+    inner: R,
+    spinner: Spinner,
+    count: ByteCount,
+}
+
+impl<R> SpinnerReader<R> {
+    pub fn new(inner: R, spinner: Spinner) -> Self {
+        Self {
+            inner,
+            spinner: track_bytes(spinner),
+            count: ByteCount::default(),
+        }
+    }
+
+    /// Sets the expected size, turning the spinner into a progress bar.
+    pub fn total(mut self, bytes: u64) -> Self {
+        let _ = self.spinner.set_total(bytes);
+        self
+    }
+
+    pub fn get_ref(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut R {
+        &mut self.inner
+    }
+
+    /// Reports the bytes counted so far before handing out the spinner.
+    pub fn spinner_mut(&mut self) -> &mut Spinner {
+        self.count.report(&mut self.spinner);
+        &mut self.spinner
+    }
+
+    pub fn into_parts(mut self) -> (R, Spinner) {
+        self.count.report(&mut self.spinner);
+        (self.inner, self.spinner)
+    }
+}
+
+impl<R> Read for SpinnerReader<R>
+where
+    R: Read,
+{
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let read = self.inner.read(buf)?;
+        match read {
+            0 => self.count.report(&mut self.spinner),
+            read => self.count.add(&mut self.spinner, read),
+        }
+        Ok(read)
+    }
+}
+
+impl<R> BufRead for SpinnerReader<R>
+where
+    R: BufRead,
+{
+    fn fill_buf(&mut self) -> io::Result<&[u8]> {
+        let buf = self.inner.fill_buf()?;
+        if buf.is_empty() {
+            self.count.report(&mut self.spinner);
+        }
+        Ok(buf)
+    }
+
+    fn consume(&mut self, amount: usize) {
+        self.inner.consume(amount);
+        self.count.add(&mut self.spinner, amount);
+    }
+}
+
+/// Counts the bytes written through it and reports them to a spinner, which
+/// shows the amount transferred and the throughput.
+pub struct SpinnerWriter<W> {
+    inner: W,
+    spinner: Spinner,
+    count: ByteCount,
+}
+
+impl<W> SpinnerWriter<W> {
+    pub fn new(inner: W, spinner: Spinner) -> Self {
+        Self {
+            inner,
+            spinner: track_bytes(spinner),
+            count: ByteCount::default(),
+        }
+    }
+
+    /// Sets the expected size, turning the spinner into a progress bar.
+    pub fn total(mut self, bytes: u64) -> Self {
+        let _ = self.spinner.set_total(bytes);
+        self
+    }
+
+    pub fn get_ref(&self) -> &W {
+        &self.inner
+    }
+
+    pub fn get_mut(&mut self) -> &mut W {
+        &mut self.inner
+    }
+
+    /// Reports the bytes counted so far before handing out the spinner.
+    pub fn spinner_mut(&mut self) -> &mut Spinner {
+        self.count.report(&mut self.spinner);
+        &mut self.spinner
+    }
+
+    pub fn into_parts(mut self) -> (W, Spinner) {
+        self.count.report(&mut self.spinner);
+        (self.inner, self.spinner)
+    }
+}
+
+impl<W> Write for SpinnerWriter<W>
+where
+    W: Write,
+{
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count.add(&mut self.spinner, written);
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.count.report(&mut self.spinner);
+        self.inner.flush()
+    }
+}
+
+/// Bytes transferred but not reported to the spinner yet.
+#[derive(Default)]
+struct ByteCount {
+    pending: u64,
+    reported_at: Option<Instant>,
+}
+
+impl ByteCount {
+    fn add(&mut self, spinner: &mut Spinner, bytes: usize) {
+        self.pending += bytes as u64;
+        let now = spinner.clock().now();
+        let due = self
+            .reported_at
+            .is_none_or(|at| now.saturating_duration_since(at) >= REPORT_INTERVAL);
+        if due {
+            self.reported_at = Some(now);
+            self.report(spinner);
+        }
+    }
+
+    fn report(&mut self, spinner: &mut Spinner) {
+        if self.pending > 0 {
+            let _ = spinner.inc(mem::take(&mut self.pending));
+        }
+    }
+}
+
+fn track_bytes(mut spinner: Spinner) -> Spinner {
+    let _ = spinner.set_progress_unit(ProgressUnit::Bytes);
+    if !spinner.is_running() {
+        let _ = spinner.start();
+    }
+    spinner
+}
+
+#[cfg(test)]
+mod tests {
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::{CaptureWriter, ManualClock};
+    use crate::{RenderMode, SpinnerBuilder};
+
+    fn spinner_into(buffer: &CaptureWriter) -> Spinner {
+        let mut spinner = Spinner::new("Copying");
+        spinner.set_output_stream(buffer.clone()).unwrap();
+        spinner.set_render_mode(RenderMode::Animated).unwrap();
+        spinner
+    }
+
+    #[test]
+    fn test_reader_passes_data_through() {
+        let data = vec![7u8; 4096];
+        let mut reader = SpinnerReader::new(&data[..], spinner_into(&CaptureWriter::new()));
+        assert!(reader.spinner_mut().is_running());
+
+        let mut copied = Vec::new();
+        assert_eq!(io::copy(&mut reader, &mut copied).unwrap(), 4096);
+        assert_eq!(copied, data);
+
+        let (rest, mut spinner) = reader.into_parts();
+        assert!(rest.is_empty());
+        spinner.stop().unwrap();
+    }
+
+    #[test]
+    fn test_reader_reports_progress() {
+        let buffer = CaptureWriter::new();
+        let data = vec![0u8; 2048];
+        let mut reader = SpinnerReader::new(&data[..], spinner_into(&buffer)).total(2048);
+        let mut chunk = [0u8; 1024];
+        reader.read_exact(&mut chunk).unwrap();
+        thread::sleep(Duration::from_millis(200));
+        reader.spinner_mut().stop().unwrap();
+
+        let output = buffer.contents();
+        assert!(output.contains(" 50% 1.0 KiB/2.0 KiB "));
+        assert!(output.contains("B/s ETA "));
+    }
+
+    #[test]
+    fn test_buf_reader_counts_consumed_bytes() {
+        let buffer = CaptureWriter::new();
+        let data = b"first line\nsecond line\n";
+        let mut reader = SpinnerReader::new(&data[..], spinner_into(&buffer));
+        let lines: Vec<_> = (&mut reader).lines().map(Result::unwrap).collect();
+        assert_eq!(lines, vec!["first line", "second line"]);
+        thread::sleep(Duration::from_millis(100));
+        reader.spinner_mut().stop().unwrap();
+
+        assert!(buffer.contents().contains("23 B "));
+    }
+
+    #[test]
+    fn test_small_reads_are_reported_in_batches() {
+        let clock = ManualClock::new();
+        let spinner = SpinnerBuilder::new("Copying")
+            .render_mode(RenderMode::Hidden)
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+        let mut reader = SpinnerReader::new(&[0u8; 100][..], spinner);
+        let mut byte = [0u8; 1];
+        for _ in 0..10 {
+            reader.read_exact(&mut byte).unwrap();
+        }
+        assert_eq!(reader.count.pending, 9);
+
+        clock.advance(REPORT_INTERVAL);
+        reader.read_exact(&mut byte).unwrap();
+        assert_eq!(reader.count.pending, 0);
+
+        reader.read_exact(&mut byte).unwrap();
+        assert_eq!(reader.count.pending, 1);
+        io::copy(&mut reader, &mut io::sink()).unwrap();
+        assert_eq!(reader.count.pending, 0);
+        reader.spinner_mut().stop().unwrap();
+    }
+
+    #[test]
+    fn test_writer_reports_bytes_without_total() {
+        let buffer = CaptureWriter::new();
+        let mut writer = SpinnerWriter::new(Vec::new(), spinner_into(&buffer));
+        writer.write_all(&[1u8; 3000]).unwrap();
+        writer.flush().unwrap();
+        thread::sleep(Duration::from_millis(100));
+        writer.spinner_mut().stop().unwrap();
+
+        assert_eq!(writer.get_ref().len(), 3000);
+        let output = buffer.contents();
+        assert!(output.contains("2.9 KiB "));
+        assert!(!output.contains('%'));
+    }
+}
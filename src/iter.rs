@@ -0,0 +1,327 @@
+use std::mem;
+use std::time::Instant;
+
+use crate::io::REPORT_INTERVAL;
+use crate::spinner::state::trim_trailing_dots;
+use crate::Spinner;
+
+/// Drives a spinner from an iterator: the position advances with every item,
+/// and the spinner finishes once the iterator is exhausted.
+pub trait SpinnerIteratorExt: Iterator + Sized {
+    fn spin<M>(self, message: M) -> SpinnerIter<Self>
+    where
+        M: Into<String>,
+    {
+        let message = message.into();
+        let (finish_message, _) = trim_trailing_dots(message.clone());
+        SpinnerIter::new(self, Spinner::new(message), Some(finish_message))
+    }
+
+    /// Uses an already configured spinner, which is started if it is not
+    /// running yet and stopped without a final line unless
+    /// `finish_message` is set.
+    fn progress_with(self, spinner: Spinner) -> SpinnerIter<Self> {
+        SpinnerIter::new(self, spinner, None)
+    }
+}
+
+impl<I> SpinnerIteratorExt for I where I: Iterator {}
+
+type MessageFn<T> = Box<dyn FnMut(&T) -> String + Send>;
+
+pub struct SpinnerIter<I>
+where
+    I: Iterator,
+{
+    iter: I,
+    spinner: Spinner,
+    message: Option<MessageFn<I::Item>>,
+    finish_message: Option<String>,
+    position: Position,
+    started: bool,
+    finished: bool,
+}
+
+impl<I> SpinnerIter<I>
+where
+    I: Iterator,
+{
+    fn new(iter: I, spinner: Spinner, finish_message: Option<String>) -> Self {
+        Self {
+            iter,
+            spinner,
+            message: None,
+            finish_message,
+            position: Position::default(),
+            started: false,
+            finished: false,
+        }
+    }
+
+    /// Sets the spinner message from each item as it is handed out, e.g. the
+    /// name of the file about to be processed.
+    pub fn with_message<F>(mut self, message: F) -> Self
+    where
+        F: FnMut(&I::Item) -> String + Send + 'static,
+    {
+        self.message = Some(Box::new(message));
+        self
+    }
+
+    pub fn finish_message<T>(mut self, message: T) -> Self
+    where
+        T: Into<String>,
+    {
+        self.finish_message = Some(message.into());
+        self
+    }
+
+    fn start(&mut self) {
+        self.started = true;
+        if let (lower, Some(upper)) = self.iter.size_hint() {
+            if lower == upper {
+                let _ = self.spinner.set_total(upper as u64);
+            }
+        }
+        if !self.spinner.is_running() {
+            let _ = self.spinner.start();
+        }
+    }
+
+    fn finish(&mut self) {
+        self.finished = true;
+        if !self.spinner.is_running() {
+            return;
+        }
+        self.position.report(&mut self.spinner);
+        let _ = match &self.finish_message {
+            Some(message) => self.spinner.succeed(message),
+            None => self.spinner.stop(),
+        };
+    }
+}
+
+impl<I> Iterator for SpinnerIter<I>
+where
+    I: Iterator,
+{
+    type Item = I::Item;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.finished {
+            return None;
+        }
+        if !self.started {
+            self.start();
+        }
+
+        match self.iter.next() {
+            Some(item) => {
+                let message = self.message.as_mut().map(|message| message(&item));
+                self.position.add(&mut self.spinner, message);
+                Some(item)
+            }
+            None => {
+                self.finish();
+                None
+            }
+        }
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.finished {
+            true => (0, Some(0)),
+            false => self.iter.size_hint(),
+        }
+    }
+}
+
+/// Items handed out but not reported to the spinner yet, batched like the
+/// byte counts of `SpinnerReader`.
+#[derive(Default)]
+struct Position {
+    pending: u64,
+    /// The message of the latest item, if it differs from the one shown.
+    message: Option<String>,
+    shown: Option<String>,
+    reported_at: Option<Instant>,
+}
+
+impl Position {
+    fn add(&mut self, spinner: &mut Spinner, message: Option<String>) {
+        self.pending += 1;
+        if let Some(message) = message {
+            self.message = (self.shown.as_ref() != Some(&message)).then_some(message);
+        }
+        let now = spinner.clock().now();
+        let due = self
+            .reported_at
+            .is_none_or(|at| now.saturating_duration_since(at) >= REPORT_INTERVAL);
+        if due {
+            self.reported_at = Some(now);
+            self.report(spinner);
+        }
+    }
+
+    fn report(&mut self, spinner: &mut Spinner) {
+        if let Some(message) = self.message.take() {
+            let _ = spinner.set_message(message.clone());
+            self.shown = Some(message);
+        }
+        if self.pending > 0 {
+            let _ = spinner.inc(mem::take(&mut self.pending));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::Duration;
+
+    use super::*;
+    use crate::testing::{CaptureWriter, ManualClock};
+    use crate::{RenderMode, SpinnerBuilder, SpinnerEvent};
+
+    fn hidden_spinner() -> Spinner {
+        let mut spinner = Spinner::new("Processing");
+        spinner.set_render_mode(RenderMode::Hidden).unwrap();
+        spinner
+    }
+
+    fn record_finish(spinner: &mut Spinner) -> Arc<Mutex<Vec<String>>> {
+        let finished = Arc::new(Mutex::new(Vec::new()));
+        let recorded = finished.clone();
+        spinner.on_finish(move |symbol, text| {
+            recorded
+                .lock()
+                .unwrap()
+                .push(format!("{} {}", symbol, text));
+        });
+        finished
+    }
+
+    #[test]
+    fn test_progress_with_yields_all_items() {
+        let items: Vec<_> = (1..=5).progress_with(hidden_spinner()).collect();
+        assert_eq!(items, vec![1, 2, 3, 4, 5]);
+    }
+
+    #[test]
+    fn test_spinner_runs_while_iterating() {
+        let mut iter = vec!["a", "b"].into_iter().progress_with(hidden_spinner());
+        assert!(!iter.spinner.is_running());
+        assert_eq!(iter.next(), Some("a"));
+        assert!(iter.spinner.is_running());
+        assert_eq!(iter.next(), Some("b"));
+        assert_eq!(iter.next(), None);
+        assert!(!iter.spinner.is_running());
+        assert_eq!(iter.next(), None);
+        assert_eq!(iter.size_hint(), (0, Some(0)));
+    }
+
+    #[test]
+    fn test_finish_message() {
+        let mut spinner = hidden_spinner();
+        let finished = record_finish(&mut spinner);
+        let count = (0..3)
+            .progress_with(spinner)
+            .finish_message("Processed 3 files")
+            .count();
+        assert_eq!(count, 3);
+        assert_eq!(*finished.lock().unwrap(), vec!["✔ Processed 3 files"]);
+    }
+
+    #[test]
+    fn test_progress_with_stops_without_finish_message() {
+        let mut spinner = hidden_spinner();
+        let finished = record_finish(&mut spinner);
+        assert_eq!((0..3).progress_with(spinner).count(), 3);
+        assert!(finished.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn test_spin_uses_message_as_finish_message() {
+        let mut iter = std::iter::empty::<u32>().spin("Processing...");
+        iter.spinner.set_render_mode(RenderMode::Hidden).unwrap();
+        let finished = record_finish(&mut iter.spinner);
+        assert_eq!(iter.count(), 0);
+        assert_eq!(*finished.lock().unwrap(), vec!["✔ Processing"]);
+    }
+
+    #[test]
+    fn test_with_message_is_called_per_item() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorded = seen.clone();
+        let files = vec!["a.txt", "b.txt"];
+        let count = files
+            .into_iter()
+            .progress_with(hidden_spinner())
+            .with_message(move |file| {
+                recorded.lock().unwrap().push(file.to_string());
+                format!("Processing {}", file)
+            })
+            .count();
+        assert_eq!(count, 2);
+        assert_eq!(*seen.lock().unwrap(), vec!["a.txt", "b.txt"]);
+    }
+
+    #[test]
+    fn test_items_are_reported_in_batches() {
+        let clock = ManualClock::new();
+        let spinner = SpinnerBuilder::new("Processing")
+            .render_mode(RenderMode::Hidden)
+            .clock(clock.clone())
+            .build()
+            .unwrap();
+        let changes = spinner.subscribe();
+        let mut iter = (0..100)
+            .progress_with(spinner)
+            .with_message(|n| format!("Batch {}", n / 50));
+        for _ in 0..10 {
+            iter.next().unwrap();
+        }
+        assert_eq!(iter.position.pending, 9);
+        assert_eq!(iter.position.message, None);
+
+        clock.advance(REPORT_INTERVAL);
+        iter.next().unwrap();
+        assert_eq!(iter.position.pending, 0);
+
+        assert_eq!(iter.by_ref().count(), 89);
+        assert_eq!(iter.position.pending, 0);
+        let messages: Vec<_> = changes
+            .try_iter()
+            .filter_map(|event| match event {
+                SpinnerEvent::MessageChange { new, .. } => Some(new),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(messages, vec!["Batch 0", "Batch 1"]);
+    }
+
+    fn render_while_iterating<I: Iterator>(iter: I) -> String {
+        let buffer = CaptureWriter::new();
+        let mut spinner = Spinner::new("Processing");
+        spinner.set_output_stream(buffer.clone()).unwrap();
+        spinner.set_render_mode(RenderMode::Animated).unwrap();
+        for _ in iter.progress_with(spinner) {
+            thread::sleep(Duration::from_millis(30));
+        }
+        buffer.contents()
+    }
+
+    #[test]
+    fn test_known_length_switches_to_progress() {
+        let output = render_while_iterating(0..3);
+        assert!(output.contains("/3"));
+    }
+
+    #[test]
+    fn test_unknown_length_stays_indeterminate() {
+        let output = render_while_iterating((0..6).filter(|n| n % 2 == 0));
+        assert!(output.contains("Processing"));
+        assert!(!output.contains('%'));
+    }
+}
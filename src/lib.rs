@@ -1,12 +1,42 @@
 pub use colored::Color;
+pub use config::{SpinnerConfig, Symbols};
 pub use error::{SpinnerError, SpinnerResult};
+pub use event_emitter::Subscription;
+#[cfg(feature = "async")]
+pub use future::{SpinnerFutureExt, WithSpinner};
+pub use guard::{InstalledGuard, TerminalGuard};
+pub use io::{SpinnerReader, SpinnerWriter};
+pub use iter::{SpinnerIter, SpinnerIteratorExt};
+#[cfg(feature = "log")]
+pub use logger::SpinnerLogger;
 pub use spinner::alignment::Alignment;
-pub use spinner::builtins::SpinnerStyle;
-pub use spinner::event::Event;
+pub use spinner::builder::SpinnerBuilder;
+pub use spinner::builtins::{SpinnerData, SpinnerStyle};
+pub use spinner::color::ColorPolicy;
+pub use spinner::event::{Event, Outcome, SpinnerEvent};
+pub use spinner::multi::{ChildSpinner, MultiSpinner};
+pub use spinner::progress::ProgressUnit;
+pub use spinner::registry::suspend;
+pub use spinner::render_mode::RenderMode;
 pub use spinner::stream::SpinnerStream;
-pub use spinner::Spinner;
+pub use spinner::styles::{load_styles_from_json, load_styles_from_toml, register_style, StyleRef};
+pub use spinner::template::Template;
+pub use spinner::{Spinner, FAILURE_SYMBOL, INFO_SYMBOL, SUCCESS_SYMBOL, WARNING_SYMBOL};
+#[cfg(feature = "tracing")]
+pub use tracing_layer::SpinnerLayer;
 
+mod clock;
 mod config;
 mod error;
 mod event_emitter;
+#[cfg(feature = "async")]
+mod future;
+mod guard;
+mod io;
+mod iter;
+#[cfg(feature = "log")]
+mod logger;
 mod spinner;
+pub mod testing;
+#[cfg(feature = "tracing")]
+mod tracing_layer;
@@ -0,0 +1,189 @@
+use std::io::Write;
+use std::sync::Mutex;
+
+use colored::Color;
+use log::{Level, LevelFilter, Log, Metadata, Record};
+
+use crate::spinner::color::{paint, ColorPolicy};
+use crate::spinner::registry::{lock, ACTIVE_SPINNERS};
+use crate::{SpinnerError, SpinnerResult, SpinnerStream};
+
+/// A `log::Log` implementation that prints records above the active spinners,
+/// either by itself or by forwarding them to another logger.
+pub struct SpinnerLogger {
+    inner: Option<Box<dyn Log>>,
+    output: Mutex<SpinnerStream>,
+    level: LevelFilter,
+    color_policy: ColorPolicy,
+}
+
+impl SpinnerLogger {
+    pub fn new() -> Self {
+        Self {
+            inner: None,
+            output: Mutex::new(SpinnerStream::Stderr),
+            level: LevelFilter::Info,
+            color_policy: ColorPolicy::default(),
+        }
+    }
+
+    /// Forwards records to `logger`, e.g. an `env_logger::Logger`, while the
+    /// spinners are suspended.
+    pub fn wrap<L>(logger: L) -> Self
+    where
+        L: Log + 'static,
+    {
+        Self {
+            inner: Some(Box::new(logger)),
+            ..Self::new()
+        }
+    }
+
+    pub fn level(mut self, level: LevelFilter) -> Self {
+        self.level = level;
+        self
+    }
+
+    pub fn output_stream(mut self, stream: SpinnerStream) -> Self {
+        self.output = Mutex::new(stream);
+        self
+    }
+
+    pub fn color_policy<T>(mut self, color_policy: T) -> Self
+    where
+        T: Into<ColorPolicy>,
+    {
+        self.color_policy = color_policy.into();
+        self
+    }
+
+    pub fn init(self) -> SpinnerResult<()> {
+        let level = self.level;
+        log::set_boxed_logger(Box::new(self)).map_err(|e| SpinnerError::Runtime(e.to_string()))?;
+        log::set_max_level(level);
+        Ok(())
+    }
+
+    fn write_record(&self, record: &Record) {
+        let mut w = lock(&self.output);
+        let color = match record.level() {
+            Level::Error => Color::Red,
+            Level::Warn => Color::Yellow,
+            Level::Info => Color::Green,
+            Level::Debug => Color::Blue,
+            Level::Trace => Color::Magenta,
+        };
+        let level = format!("{:<5}", record.level());
+        let level = match self.color_policy.should_colorize(|| w.is_terminal()) {
+            true => paint(&level, Some(color)),
+            false => level,
+        };
+        let _ = writeln!(w, "{} {}", level, record.args());
+        let _ = w.flush();
+    }
+}
+
+impl Default for SpinnerLogger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Log for SpinnerLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= self.level
+            && self
+                .inner
+                .as_ref()
+                .is_none_or(|inner| inner.enabled(metadata))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        ACTIVE_SPINNERS.suspend_all(|| match &self.inner {
+            Some(inner) => {
+                inner.log(record);
+                inner.flush();
+            }
+            None => self.write_record(record),
+        });
+    }
+
+    fn flush(&self) {
+        match &self.inner {
+            Some(inner) => inner.flush(),
+            None => {
+                let _ = lock(&self.output).flush();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::CaptureWriter;
+
+    fn log_to(logger: &SpinnerLogger, level: Level, message: &str) {
+        logger.log(
+            &Record::builder()
+                .level(level)
+                .args(format_args!("{}", message))
+                .build(),
+        );
+    }
+
+    #[test]
+    fn test_logger_writes_records() {
+        let buffer = CaptureWriter::new();
+        let logger = SpinnerLogger::new().output_stream(SpinnerStream::custom(buffer.clone()));
+        log_to(&logger, Level::Warn, "disk almost full");
+        assert_eq!(buffer.contents(), "WARN  disk almost full\n");
+    }
+
+    #[test]
+    fn test_logger_filters_by_level() {
+        let buffer = CaptureWriter::new();
+        let logger = SpinnerLogger::new()
+            .level(LevelFilter::Warn)
+            .output_stream(SpinnerStream::custom(buffer.clone()));
+        log_to(&logger, Level::Info, "hidden");
+        log_to(&logger, Level::Error, "shown");
+        assert_eq!(buffer.contents(), "ERROR shown\n");
+    }
+
+    #[test]
+    fn test_logger_colors_level_when_forced() {
+        let buffer = CaptureWriter::new();
+        let logger = SpinnerLogger::new()
+            .color_policy(ColorPolicy::Always)
+            .output_stream(SpinnerStream::custom(buffer.clone()));
+        log_to(&logger, Level::Error, "failed");
+        assert_eq!(buffer.contents(), "\x1B[31mERROR\x1B[0m failed\n");
+    }
+
+    #[test]
+    fn test_logger_forwards_to_inner_logger() {
+        struct Inner(CaptureWriter);
+
+        impl Log for Inner {
+            fn enabled(&self, metadata: &Metadata) -> bool {
+                metadata.level() <= Level::Warn
+            }
+
+            fn log(&self, record: &Record) {
+                writeln!(self.0.clone(), "inner: {}", record.args()).unwrap();
+            }
+
+            fn flush(&self) {}
+        }
+
+        let buffer = CaptureWriter::new();
+        let logger = SpinnerLogger::wrap(Inner(buffer.clone()));
+        log_to(&logger, Level::Info, "hidden");
+        log_to(&logger, Level::Warn, "shown");
+        assert_eq!(buffer.contents(), "inner: shown\n");
+    }
+}
@@ -1,4 +1,4 @@
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum Alignment {
     Left,
     Center,
@@ -9,14 +9,15 @@ impl Alignment {
     pub fn get_horizontal_padding(&self, terminal_width: usize, text_width: usize) -> String {
         let padding = match self {
             Alignment::Left => 0,
-            Alignment::Center => (terminal_width - text_width) / 2,
-            Alignment::Right => terminal_width - text_width,
+            Alignment::Center => terminal_width.saturating_sub(text_width) / 2,
+            Alignment::Right => terminal_width.saturating_sub(text_width),
         };
 
         " ".repeat(padding)
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for Alignment {
     fn default() -> Self {
         Alignment::Left
@@ -83,6 +84,13 @@ mod tests {
         assert_eq!(padding, expected_padding);
     }
 
+    #[test]
+    fn test_get_horizontal_padding_text_wider_than_terminal() {
+        let alignment = Alignment::Right;
+        let padding = alignment.get_horizontal_padding(5, 10);
+        assert_eq!(padding, "");
+    }
+
     #[test]
     fn test_from_invalid() {
         let alignment_str = "invalid";
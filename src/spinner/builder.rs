@@ -0,0 +1,302 @@
+use std::sync::Arc;
+use std::time::Duration;
+
+use colored::Color;
+
+use super::{
+    alignment::Alignment, message::UpdateMessage, render_mode::RenderMode, state::SpinnerState,
+    stream::SpinnerStream, styles::StyleRef, template::Template, Spinner,
+};
+use crate::{clock::Clock, config::SpinnerConfig, SpinnerError, SpinnerResult};
+
+/// Configures a spinner before it starts, so that its very first frame is
+/// already drawn with the requested settings.
+///
+/// Settings left out fall back to the user's [`SpinnerConfig`], or to the
+/// one passed to [`SpinnerBuilder::config`]. Nothing is
+/// checked until [`SpinnerBuilder::build`], which reports the
+/// first invalid setting.
+pub struct SpinnerBuilder {
+    message: String,
+    style: Option<StyleRef>,
+    frames: Option<Vec<String>>,
+    interval: Option<SpinnerResult<Duration>>,
+    colors: Option<(Option<Color>, Option<Color>, Option<Color>)>,
+    alignment: Option<Alignment>,
+    stream: Option<SpinnerResult<SpinnerStream>>,
+    reverse: bool,
+    template: Option<String>,
+    render_mode: Option<RenderMode>,
+    clock: Option<Arc<dyn Clock>>,
+    config: Option<SpinnerConfig>,
+}
+
+impl SpinnerBuilder {
+    pub fn new(message: impl Into<String>) -> Self {
+        Self {
+            message: message.into(),
+            style: None,
+            frames: None,
+            interval: None,
+            colors: None,
+            alignment: None,
+            stream: None,
+            reverse: false,
+            template: None,
+            render_mode: None,
+            clock: None,
+            config: None,
+        }
+    }
+
+    pub fn style(mut self, style: impl Into<StyleRef>) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+
+    /// Uses custom frames instead of the ones of the style.
+    pub fn frames<S>(mut self, frames: &[S]) -> Self
+    where
+        S: AsRef<str>,
+    {
+        self.frames = Some(frames.iter().map(|s| s.as_ref().to_string()).collect());
+        self
+    }
+
+    /// Overrides the frame duration of the style.
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.interval = Some(match interval.is_zero() {
+            true => Err(SpinnerError::InvalidRate {
+                name: "interval".to_string(),
+                value: 0.0,
+            }),
+            false => Ok(interval),
+        });
+        self
+    }
+
+    pub fn fps<V>(mut self, fps: V) -> Self
+    where
+        V: Into<f64>,
+    {
+        self.interval = Some(frames_per_second(fps.into()));
+        self
+    }
+
+    pub fn colors<U>(mut self, style_color: U, message_color: U, dots_color: U) -> Self
+    where
+        U: Into<Option<Color>>,
+    {
+        self.colors = Some((style_color.into(), message_color.into(), dots_color.into()));
+        self
+    }
+
+    pub fn alignment(mut self, alignment: impl Into<Alignment>) -> Self {
+        self.alignment = Some(alignment.into());
+        self
+    }
+
+    pub fn output_stream<S>(mut self, stream: S) -> Self
+    where
+        S: TryInto<SpinnerStream>,
+        S::Error: Into<SpinnerError>,
+    {
+        self.stream = Some(stream.try_into().map_err(Into::into));
+        self
+    }
+
+    pub fn reverse(mut self, reverse: bool) -> Self {
+        self.reverse = reverse;
+        self
+    }
+
+    pub fn template(mut self, template: impl Into<String>) -> Self {
+        self.template = Some(template.into());
+        self
+    }
+
+    pub fn render_mode(mut self, render_mode: impl Into<RenderMode>) -> Self {
+        self.render_mode = Some(render_mode.into());
+        self
+    }
+
+    /// Takes time and frame delays from `clock` instead of the system, e.g.
+    /// a `testing::ManualClock` that tests advance by hand.
+    pub fn clock(mut self, clock: impl Clock + 'static) -> Self {
+        self.clock = Some(Arc::new(clock));
+        self
+    }
+
+    /// Starts from `config` instead of [`SpinnerConfig::global`], so that the
+    /// end user's config file and `SPINERGY_*` variables are not consulted.
+    pub fn config(mut self, config: SpinnerConfig) -> Self {
+        self.config = Some(config);
+        self
+    }
+
+    /// Starts from an empty config, leaving every setting to the
+    /// application.
+    pub fn ignore_config(self) -> Self {
+        self.config(SpinnerConfig::default())
+    }
+
+    /// Validates the configuration and creates the spinner. The spinner is
+    /// not started.
+    pub fn build(self) -> SpinnerResult<Spinner> {
+        let mut state = SpinnerState::new(self.message);
+        if let Some(config) = self.config {
+            state.set_config(Arc::new(config));
+        }
+        state.config().apply(&mut state);
+
+        if let Some(style) = self.style {
+            let data = style.resolve()?;
+            state.apply(UpdateMessage::Style(style, data))?;
+        }
+        if let Some(frames) = self.frames {
+            validate_frames(&frames)?;
+            state.apply(UpdateMessage::Frames(frames))?;
+        }
+        if let Some(interval) = self.interval {
+            state.apply(UpdateMessage::FramesPerSecond(
+                1.0 / interval?.as_secs_f64(),
+            ))?;
+        }
+        if let Some((style_color, message_color, dots_color)) = self.colors {
+            state.apply(UpdateMessage::Colors(
+                style_color,
+                message_color,
+                dots_color,
+            ))?;
+        }
+        if let Some(alignment) = self.alignment {
+            state.apply(UpdateMessage::Alignment(alignment))?;
+        }
+        if let Some(stream) = self.stream {
+            state.apply(UpdateMessage::Stream(stream?))?;
+        }
+        if let Some(template) = self.template {
+            state.apply(UpdateMessage::Template(Some(Template::parse(&template)?)))?;
+        }
+        if let Some(render_mode) = self.render_mode {
+            state.apply(UpdateMessage::RenderMode(render_mode))?;
+        }
+        state.set_reverse(self.reverse);
+        if let Some(clock) = self.clock {
+            state.set_clock(clock);
+        }
+
+        Ok(Spinner::with_state(state))
+    }
+}
+
+pub(crate) fn validate_frames(frames: &[String]) -> SpinnerResult<()> {
+    match frames.is_empty() {
+        true => Err(SpinnerError::InvalidFrames),
+        false => Ok(()),
+    }
+}
+
+pub(crate) fn validate_rate(name: &str, value: f64) -> SpinnerResult<()> {
+    match value.is_finite() && value > 0.0 {
+        true => Ok(()),
+        false => Err(SpinnerError::InvalidRate {
+            name: name.to_string(),
+            value,
+        }),
+    }
+}
+
+pub(crate) fn frames_per_second(fps: f64) -> SpinnerResult<Duration> {
+    validate_rate("fps", fps)?;
+    Ok(Duration::from_secs_f64(1.0 / fps))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::CaptureWriter;
+
+    #[test]
+    fn test_build_default() {
+        let spinner = SpinnerBuilder::new("Loading").build().unwrap();
+        assert!(!spinner.is_running());
+    }
+
+    #[test]
+    fn test_build_rejects_empty_frames() {
+        let frames: [&str; 0] = [];
+        let result = SpinnerBuilder::new("Loading").frames(&frames).build();
+        assert!(matches!(result, Err(SpinnerError::InvalidFrames)));
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_rates() {
+        assert!(SpinnerBuilder::new("Loading").fps(0).build().is_err());
+        assert!(SpinnerBuilder::new("Loading")
+            .fps(f64::NAN)
+            .build()
+            .is_err());
+        assert!(SpinnerBuilder::new("Loading")
+            .interval(Duration::ZERO)
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_build_rejects_invalid_stream_and_template() {
+        assert!(SpinnerBuilder::new("Loading")
+            .output_stream("nowhere")
+            .build()
+            .is_err());
+        assert!(SpinnerBuilder::new("Loading")
+            .template("{nope}")
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_first_frame_uses_configuration() {
+        let buffer = CaptureWriter::new();
+        let mut spinner = SpinnerBuilder::new("Loading")
+            .frames(&["<>"])
+            .colors(None, None, None)
+            .output_stream(buffer.clone())
+            .render_mode(RenderMode::Animated)
+            .template("{spinner}|{msg}")
+            .build()
+            .unwrap();
+        spinner.start().unwrap();
+        std::thread::sleep(Duration::from_millis(20));
+        spinner.stop().unwrap();
+        assert!(buffer.contents().starts_with("\x1B[?25l\r\x1B[K<>|Loading"));
+    }
+
+    #[test]
+    fn test_config_replaces_the_global_config() {
+        let config = SpinnerConfig {
+            render_mode: Some(RenderMode::Hidden),
+            symbols: crate::Symbols {
+                success: Some("ok".to_string()),
+                ..Default::default()
+            },
+            ..SpinnerConfig::default()
+        };
+        let spinner = SpinnerBuilder::new("Loading")
+            .config(config.clone())
+            .render_mode(RenderMode::Animated)
+            .build()
+            .unwrap();
+        assert_eq!(spinner.state.render_mode(), RenderMode::Hidden);
+        assert_eq!(*spinner.state.config(), config);
+
+        let spinner = SpinnerBuilder::new("Loading")
+            .config(config)
+            .ignore_config()
+            .render_mode(RenderMode::Animated)
+            .build()
+            .unwrap();
+        assert_eq!(spinner.state.render_mode(), RenderMode::Animated);
+        assert_eq!(*spinner.state.config(), SpinnerConfig::default());
+    }
+}
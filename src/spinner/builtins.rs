@@ -1,8 +1,11 @@
 use std::collections::HashMap;
+use std::str::FromStr;
 
 use lazy_static::lazy_static;
 use serde::{Deserialize, Serialize};
-use strum::{Display, EnumIter};
+use strum::{Display, EnumIter, IntoEnumIterator};
+
+use crate::{SpinnerError, SpinnerResult};
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
 pub struct SpinnerData {
@@ -98,19 +101,83 @@ pub enum SpinnerStyle {
     Weather,
 }
 
-impl<'a> Into<SpinnerStyle> for &'a str {
-    fn into(self) -> SpinnerStyle {
-        let style = SpinnerStyle::deserialize(&serde_json::to_value(self).unwrap());
-        style.expect(&format!("Unsupported spinner style: {}", self))
+/// Lowercases the name and drops separators, so that `Dots8Bit`, `dots-8-bit`
+/// and `dots_8_bit` all compare equal.
+fn normalize(name: &str) -> String {
+    name.chars()
+        .filter(|c| !matches!(c, '-' | '_' | ' '))
+        .flat_map(char::to_lowercase)
+        .collect()
+}
+
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, ca) in a.chars().enumerate() {
+        let mut diagonal = row[0];
+        row[0] = i + 1;
+        for (j, cb) in b.iter().enumerate() {
+            let cost = if ca == *cb { diagonal } else { diagonal + 1 };
+            diagonal = row[j + 1];
+            row[j + 1] = cost.min(row[j] + 1).min(row[j + 1] + 1);
+        }
     }
+    row[b.len()]
 }
 
+/// Returns up to three style names close to `name`, closest first.
+fn suggestions(name: &str) -> Vec<String> {
+    const MAX_SUGGESTIONS: usize = 3;
+    let name = normalize(name);
+    let max_distance = (name.chars().count() / 3).max(2);
+    let mut candidates: Vec<(usize, String)> = SpinnerStyle::iter()
+        .map(|style| {
+            let candidate = style.to_string();
+            (edit_distance(&name, &normalize(&candidate)), candidate)
+        })
+        .filter(|(distance, _)| *distance <= max_distance)
+        .collect();
+    candidates.sort();
+    candidates
+        .into_iter()
+        .take(MAX_SUGGESTIONS)
+        .map(|(_, candidate)| candidate)
+        .collect()
+}
+
+#[allow(clippy::derivable_impls)]
 impl Default for SpinnerStyle {
     fn default() -> Self {
         Self::CircleHalves
     }
 }
 
+impl FromStr for SpinnerStyle {
+    type Err = SpinnerError;
+
+    fn from_str(value: &str) -> SpinnerResult<Self> {
+        let normalized = normalize(value);
+        if let Some(style) =
+            SpinnerStyle::iter().find(|style| normalize(&style.to_string()) == normalized)
+        {
+            return Ok(style);
+        }
+
+        Err(SpinnerError::UnknownStyle {
+            name: value.to_string(),
+            suggestions: suggestions(value),
+        })
+    }
+}
+
+impl TryFrom<&str> for SpinnerStyle {
+    type Error = SpinnerError;
+
+    fn try_from(value: &str) -> SpinnerResult<Self> {
+        value.parse()
+    }
+}
+
 lazy_static! {
     pub static ref SPINNER_COLLECTION: HashMap<SpinnerStyle, SpinnerData> = {
         let frames_str = include_str!("../data/frames.toml");
@@ -129,6 +196,7 @@ pub fn get_spinner_data(name: &SpinnerStyle) -> SpinnerData {
 }
 
 #[cfg(test)]
+#[allow(clippy::clone_on_copy)]
 mod tests {
     use strum::IntoEnumIterator;
 
@@ -212,15 +280,69 @@ mod tests {
 
     #[test]
     fn test_spinner_style_conversion() {
-        let spinner_style: SpinnerStyle = "CircleHalves".into();
+        let spinner_style = SpinnerStyle::try_from("CircleHalves").unwrap();
         assert_eq!(spinner_style, SpinnerStyle::default());
     }
 
     #[test]
-    #[should_panic]
     fn test_spinner_style_conversion_invalid() {
         let key = r#"InvalidStyle"#;
-        let _: SpinnerStyle = key.into();
+        assert!(SpinnerStyle::try_from(key).is_err());
+    }
+
+    #[test]
+    fn test_spinner_style_parse_ignores_case_and_separators() {
+        assert_eq!(
+            "dots-8-bit".parse::<SpinnerStyle>().unwrap(),
+            SpinnerStyle::Dots8Bit
+        );
+        assert_eq!(
+            "circle_halves".parse::<SpinnerStyle>().unwrap(),
+            SpinnerStyle::CircleHalves
+        );
+        assert_eq!(
+            "SIMPLE-DOTS-SCROLLING".parse::<SpinnerStyle>().unwrap(),
+            SpinnerStyle::SimpleDotsScrolling
+        );
+    }
+
+    #[test]
+    fn test_spinner_style_parse_all_styles() {
+        for style in SpinnerStyle::iter() {
+            assert_eq!(style.to_string().parse::<SpinnerStyle>().unwrap(), style);
+        }
+    }
+
+    #[test]
+    fn test_spinner_style_parse_suggests_closest() {
+        let error = "circle-halfs".parse::<SpinnerStyle>().unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown spinner style: circle-halfs. Did you mean CircleHalves?"
+        );
+
+        let error = "dotz".parse::<SpinnerStyle>().unwrap_err();
+        assert!(error
+            .to_string()
+            .starts_with("Unknown spinner style: dotz. Did you mean Dots"));
+    }
+
+    #[test]
+    fn test_spinner_style_parse_without_suggestions() {
+        let error = "definitely-not-a-spinner"
+            .parse::<SpinnerStyle>()
+            .unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "Unknown spinner style: definitely-not-a-spinner"
+        );
+    }
+
+    #[test]
+    fn test_edit_distance() {
+        assert_eq!(edit_distance("", "abc"), 3);
+        assert_eq!(edit_distance("kitten", "sitting"), 3);
+        assert_eq!(edit_distance("dots", "dots"), 0);
     }
 
     #[test]
@@ -0,0 +1,169 @@
+use std::borrow::Cow;
+use std::env;
+
+use colored::Color;
+use lazy_static::lazy_static;
+use regex::Regex;
+
+lazy_static! {
+    static ref ANSI_ESCAPE: Regex =
+        Regex::new(r"\x1B(?:\[[0-?]*[ -/]*[@-~]|\][^\x07\x1B]*(?:\x07|\x1B\\)|[@-Z\\-_])").unwrap();
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ColorPolicy {
+    /// Follows `CLICOLOR_FORCE`, `NO_COLOR` and `CLICOLOR`, in that order of
+    /// priority, and otherwise colors only when writing to a terminal.
+    #[default]
+    Auto,
+    Always,
+    Never,
+}
+
+impl ColorPolicy {
+    pub fn should_colorize<F>(self, is_terminal: F) -> bool
+    where
+        F: FnOnce() -> bool,
+    {
+        self.should_colorize_with(is_terminal, |key| env::var(key).ok())
+    }
+
+    fn should_colorize_with<F, E>(self, is_terminal: F, var: E) -> bool
+    where
+        F: FnOnce() -> bool,
+        E: Fn(&str) -> Option<String>,
+    {
+        match self {
+            ColorPolicy::Always => true,
+            ColorPolicy::Never => false,
+            ColorPolicy::Auto => {
+                if var("CLICOLOR_FORCE").is_some_and(|value| value != "0") {
+                    return true;
+                }
+                if var("NO_COLOR").is_some_and(|value| !value.is_empty()) {
+                    return false;
+                }
+                if var("CLICOLOR").is_some_and(|value| value == "0") {
+                    return false;
+                }
+                is_terminal()
+            }
+        }
+    }
+}
+
+impl From<&str> for ColorPolicy {
+    fn from(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "always" => Self::Always,
+            "never" => Self::Never,
+            _ => Self::Auto,
+        }
+    }
+}
+
+impl std::fmt::Display for ColorPolicy {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Always => write!(f, "always"),
+            Self::Never => write!(f, "never"),
+        }
+    }
+}
+
+/// Wraps `text` in SGR codes. The codes are written directly instead of going
+/// through `colored`, whose global detection would override the spinner's
+/// `ColorPolicy`.
+pub fn paint_with(text: &str, color: Option<Color>, styles: &[&str]) -> String {
+    let mut codes: Vec<Cow<str>> = styles.iter().map(|&style| style.into()).collect();
+    if let Some(color) = color {
+        codes.push(color.to_fg_str());
+    }
+    if text.is_empty() || codes.is_empty() {
+        return text.to_owned();
+    }
+    format!("\x1B[{}m{}\x1B[0m", codes.join(";"), text)
+}
+
+pub fn paint(text: &str, color: Option<Color>) -> String {
+    paint_with(text, color, &[])
+}
+
+pub fn strip_ansi(text: &str) -> Cow<'_, str> {
+    ANSI_ESCAPE.replace_all(text, "")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn env_of<'a>(vars: &'a [(&'a str, &'a str)]) -> impl Fn(&str) -> Option<String> + 'a {
+        move |key| {
+            vars.iter()
+                .find(|(name, _)| *name == key)
+                .map(|(_, value)| value.to_string())
+        }
+    }
+
+    #[test]
+    fn test_explicit_policies_ignore_environment() {
+        let env = env_of(&[("NO_COLOR", "1"), ("CLICOLOR_FORCE", "1")]);
+        assert!(ColorPolicy::Always.should_colorize_with(|| false, &env));
+        assert!(!ColorPolicy::Never.should_colorize_with(|| true, &env));
+    }
+
+    #[test]
+    fn test_auto_follows_terminal() {
+        let env = env_of(&[]);
+        assert!(ColorPolicy::Auto.should_colorize_with(|| true, &env));
+        assert!(!ColorPolicy::Auto.should_colorize_with(|| false, &env));
+    }
+
+    #[test]
+    fn test_auto_honors_no_color() {
+        let env = env_of(&[("NO_COLOR", "1")]);
+        assert!(!ColorPolicy::Auto.should_colorize_with(|| true, env));
+
+        let env = env_of(&[("NO_COLOR", "")]);
+        assert!(ColorPolicy::Auto.should_colorize_with(|| true, env));
+    }
+
+    #[test]
+    fn test_auto_honors_clicolor() {
+        let env = env_of(&[("CLICOLOR", "0")]);
+        assert!(!ColorPolicy::Auto.should_colorize_with(|| true, env));
+    }
+
+    #[test]
+    fn test_auto_clicolor_force_wins() {
+        let env = env_of(&[("CLICOLOR_FORCE", "1"), ("NO_COLOR", "1")]);
+        assert!(ColorPolicy::Auto.should_colorize_with(|| false, env));
+
+        let env = env_of(&[("CLICOLOR_FORCE", "0")]);
+        assert!(!ColorPolicy::Auto.should_colorize_with(|| false, env));
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(ColorPolicy::from("NEVER"), ColorPolicy::Never);
+        assert_eq!(ColorPolicy::from("always"), ColorPolicy::Always);
+        assert_eq!(ColorPolicy::from("invalid"), ColorPolicy::Auto);
+    }
+
+    #[test]
+    fn test_paint() {
+        assert_eq!(paint("ok", Some(Color::Green)), "\x1B[32mok\x1B[0m");
+        assert_eq!(paint("ok", None), "ok");
+        assert_eq!(
+            paint_with("ok", Some(Color::Cyan), &["1"]),
+            "\x1B[1;36mok\x1B[0m"
+        );
+    }
+
+    #[test]
+    fn test_strip_ansi() {
+        let text = "\x1B[1;31mred\x1B[0m and \x1B]8;;https://example.com\x07link\x1B]8;;\x07";
+        assert_eq!(strip_ansi(text), "red and link");
+    }
+}
@@ -1,11 +1,29 @@
 use std::fmt::{self, Display};
+use std::io;
+use std::time::Duration;
 
-#[derive(Debug)]
+use colored::Color;
+
+use super::alignment::Alignment;
+use super::styles::StyleRef;
+
+/// The kind of a [`SpinnerEvent`], used to pick which events a listener
+/// receives.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Event {
     Start,
     Stop,
     Pause,
     Resume,
+    Finish,
+    MessageChange,
+    StyleChange,
+    FramesChange,
+    ColorChange,
+    AlignmentChange,
+    /// Only emitted once enabled with `Spinner::set_frame_events`.
+    Frame,
+    Error,
 }
 
 impl Display for Event {
@@ -15,6 +33,117 @@ impl Display for Event {
             Self::Stop => write!(f, "Stop"),
             Self::Pause => write!(f, "Pause"),
             Self::Resume => write!(f, "Resume"),
+            Self::Finish => write!(f, "Finish"),
+            Self::MessageChange => write!(f, "MessageChange"),
+            Self::StyleChange => write!(f, "StyleChange"),
+            Self::FramesChange => write!(f, "FramesChange"),
+            Self::ColorChange => write!(f, "ColorChange"),
+            Self::AlignmentChange => write!(f, "AlignmentChange"),
+            Self::Frame => write!(f, "Frame"),
+            Self::Error => write!(f, "Error"),
+        }
+    }
+}
+
+/// How a spinner ended.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Outcome {
+    Success,
+    Failure,
+    Warning,
+    Info,
+    /// Finished with `stop_with` and a symbol of its own.
+    Custom,
+    /// Stopped without a result, by `stop`, by dropping the spinner or by an
+    /// interrupt.
+    Cancelled,
+}
+
+impl Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Success => write!(f, "Success"),
+            Self::Failure => write!(f, "Failure"),
+            Self::Warning => write!(f, "Warning"),
+            Self::Info => write!(f, "Info"),
+            Self::Custom => write!(f, "Custom"),
+            Self::Cancelled => write!(f, "Cancelled"),
+        }
+    }
+}
+
+/// Something that happened to a spinner, along with its details.
+///
+/// Changes, frames, errors and the finish are emitted from the render thread
+/// when it applies them, so they arrive after the call that caused them.
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum SpinnerEvent {
+    Start,
+    /// `elapsed` is the time since the spinner was started.
+    Stop {
+        elapsed: Duration,
+    },
+    /// `elapsed` is the time since the spinner was started.
+    Pause {
+        elapsed: Duration,
+    },
+    /// `paused` is the total time spent paused so far.
+    Resume {
+        paused: Duration,
+    },
+    /// A cancelled spinner has an empty symbol unless it was interrupted
+    /// with a message.
+    Finish {
+        symbol: String,
+        message: String,
+        outcome: Outcome,
+    },
+    MessageChange {
+        old: String,
+        new: String,
+    },
+    StyleChange {
+        style: StyleRef,
+    },
+    FramesChange {
+        frames: Vec<String>,
+    },
+    ColorChange {
+        spinner: Option<Color>,
+        message: Option<Color>,
+        dots: Option<Color>,
+    },
+    AlignmentChange {
+        alignment: Alignment,
+    },
+    /// `index` is the position of `frame` in the current frames.
+    Frame {
+        index: usize,
+        frame: String,
+    },
+    /// Writing to the output failed and the spinner stopped rendering.
+    Error {
+        kind: io::ErrorKind,
+        message: String,
+    },
+}
+
+impl SpinnerEvent {
+    pub fn kind(&self) -> Event {
+        match self {
+            Self::Start => Event::Start,
+            Self::Stop { .. } => Event::Stop,
+            Self::Pause { .. } => Event::Pause,
+            Self::Resume { .. } => Event::Resume,
+            Self::Finish { .. } => Event::Finish,
+            Self::MessageChange { .. } => Event::MessageChange,
+            Self::StyleChange { .. } => Event::StyleChange,
+            Self::FramesChange { .. } => Event::FramesChange,
+            Self::ColorChange { .. } => Event::ColorChange,
+            Self::AlignmentChange { .. } => Event::AlignmentChange,
+            Self::Frame { .. } => Event::Frame,
+            Self::Error { .. } => Event::Error,
         }
     }
 }
@@ -1,23 +1,58 @@
 use colored::Color;
+use crossbeam::channel::{Receiver, Sender};
 
-use crate::SpinnerResult;
-
-use super::{alignment::Alignment, builtins::SpinnerStyle, stream::SpinnerStream};
+use super::{
+    alignment::Alignment, builtins::SpinnerData, color::ColorPolicy, event::Outcome,
+    progress::ProgressUpdate, render_mode::RenderMode, stream::SpinnerStream, styles::StyleRef,
+    template::Template,
+};
 
 #[derive(Debug, Clone)]
 pub enum SpinnerMessage {
     Stop,
-    Update(SpinnerResult<UpdateMessage>),
+    Finish(FinishMessage, Outcome),
+    Update(UpdateMessage),
+    /// An update sent from within the listeners with these ids, which do not
+    /// get the events it causes.
+    ListenerUpdate(UpdateMessage, Vec<usize>),
+    Suspend(SuspendMessage),
+}
+
+/// Handshake used to hand the spinner's line over to the caller: the render
+/// thread clears the line, signals `acquired` and blocks until `released`
+/// is signalled or dropped.
+#[derive(Debug, Clone)]
+pub struct SuspendMessage {
+    pub acquired: Sender<()>,
+    pub released: Receiver<()>,
+}
+
+#[derive(Debug, Clone)]
+pub struct FinishMessage {
+    pub symbol: String,
+    pub text: String,
+    pub color: Option<Color>,
 }
 
 #[derive(Debug, Clone)]
 pub enum UpdateMessage {
     Message(String),
-    Style(SpinnerStyle),
+    Style(StyleRef, SpinnerData),
     Alignment(Alignment),
     Colors(Option<Color>, Option<Color>, Option<Color>),
     FramesPerSecond(f64),
     Speed(f64),
     Frames(Vec<String>),
     Stream(SpinnerStream),
+    Progress(ProgressUpdate),
+    Template(Option<Template>),
+    Prefix(String),
+    RenderMode(RenderMode),
+    ColorPolicy(ColorPolicy),
+    ShowElapsed(bool),
+    FrameEvents(bool),
+    /// Shows a span name in place of the message, or restores the message
+    /// shown before the first span with `None`.
+    #[cfg(feature = "tracing")]
+    SpanName(Option<String>),
 }
@@ -1,45 +1,96 @@
 use colored::Color;
+use crossbeam::channel::Receiver;
 
+#[cfg(feature = "async")]
+use self::task::RenderTask;
 use self::{
-    alignment::Alignment, builtins::SpinnerStyle, event::Event, message::UpdateMessage,
-    state::SpinnerState, stream::SpinnerStream,
+    alignment::Alignment,
+    builder::{validate_frames, validate_rate, SpinnerBuilder},
+    color::ColorPolicy,
+    event::{Event, Outcome, SpinnerEvent},
+    message::{FinishMessage, UpdateMessage},
+    progress::{ProgressUnit, ProgressUpdate},
+    registry::{ActiveLine, ActiveSpinner, ACTIVE_SPINNERS},
+    render_mode::RenderMode,
+    state::SpinnerState,
+    stream::SpinnerStream,
+    styles::StyleRef,
+    template::Template,
+};
+use crate::{
+    clock::Clock,
+    config::SpinnerConfig,
+    event_emitter::{EventEmitter, Subscription},
+    SpinnerError, SpinnerResult,
 };
-use crate::{event_emitter::EventEmitter, SpinnerError, SpinnerResult};
 use std::{
+    io::Write,
     sync::{
         atomic::{AtomicBool, Ordering},
         Arc, Condvar, Mutex,
     },
-    thread,
+    thread::{self, JoinHandle},
     time::{Duration, Instant},
 };
 
 pub mod alignment;
+pub mod builder;
 pub mod builtins;
 mod channel;
+pub mod color;
 pub mod event;
-mod message;
-mod state;
+pub(crate) mod message;
+pub mod multi;
+pub mod progress;
+pub(crate) mod registry;
+pub mod render_mode;
+pub(crate) mod state;
 pub mod stream;
+pub mod styles;
+#[cfg(feature = "async")]
+mod task;
+pub mod template;
+
+pub const SUCCESS_SYMBOL: &str = "✔";
+pub const FAILURE_SYMBOL: &str = "✖";
+pub const WARNING_SYMBOL: &str = "⚠";
+pub const INFO_SYMBOL: &str = "ℹ";
 
 pub struct Spinner {
     emitter: EventEmitter,
+    clock: Arc<dyn Clock>,
     running: Arc<AtomicBool>,
     paused: Arc<(Mutex<bool>, Condvar)>,
     state: SpinnerState,
     start_time: Option<Instant>,
+    stop_time: Option<Instant>,
     pause_start_time: Option<Instant>,
     pause_elapsed: Duration,
+    handle: Option<JoinHandle<SpinnerResult<()>>>,
+    registration: Option<usize>,
+    line: Option<Arc<dyn ActiveSpinner>>,
+    #[cfg(feature = "async")]
+    task: Option<RenderTask>,
 }
 
 impl Spinner {
     pub fn new(message: impl Into<String>) -> Self {
+        let mut state = SpinnerState::new(message);
+        SpinnerConfig::global().apply(&mut state);
+        Self::with_state(state)
+    }
+
+    /// Returns a builder to configure the spinner before its first frame.
+    pub fn builder(message: impl Into<String>) -> SpinnerBuilder {
+        SpinnerBuilder::new(message)
+    }
+
+    pub(crate) fn with_state(state: SpinnerState) -> Self {
         let running = Arc::new(AtomicBool::new(false));
         let paused = Arc::new((Mutex::new(false), Condvar::new()));
 
-        let state = SpinnerState::new(message);
-
-        let emitter = EventEmitter::new();
+        let emitter = state.emitter();
+        let clock = state.clock();
 
         let start_time = None;
         let pause_start_time = None;
@@ -47,164 +98,404 @@ impl Spinner {
 
         Self {
             emitter,
+            clock,
             running,
             state,
             paused,
             start_time,
+            stop_time: None,
             pause_start_time,
             pause_elapsed,
+            handle: None,
+            registration: None,
+            line: None,
+            #[cfg(feature = "async")]
+            task: None,
         }
     }
 
-    #[allow(unused)]
-    fn on<F, T>(&mut self, event_type: Event, mut listener: F)
+    /// Calls `listener` with every event of the given kind until the returned
+    /// subscription is dropped. Events the listener causes itself, such as a
+    /// `MessageChange` listener calling `set_message`, are not delivered back
+    /// to it, even though the render thread applies the change after the
+    /// listener returned; other listeners and subscribers still get them.
+    pub fn on<F>(&mut self, event: Event, listener: F) -> Subscription
     where
-        F: FnMut(&[T]) + Sync + Send + 'static,
-        T: 'static + Copy,
+        F: FnMut(&SpinnerEvent) + Send + 'static,
     {
-        let callback = move |args: &[Box<dyn std::any::Any>]| {
-            let typed_args: Vec<T> = args
-                .iter()
-                .map(|arg| *arg.downcast_ref().unwrap())
-                .collect();
-            listener(&typed_args);
-        };
+        self.emitter.on(event, listener)
+    }
+
+    /// Returns a channel that receives every event of the spinner, for
+    /// consumers that would rather poll than register a callback. Events are
+    /// buffered until read, so keep reading or drop the receiver, or use
+    /// `subscribe_bounded`.
+    pub fn subscribe(&self) -> Receiver<SpinnerEvent> {
+        self.emitter.subscribe()
+    }
 
-        let event_name = &event_type.to_string();
-        self.emitter.on(event_name, callback);
+    /// Like `subscribe`, but buffers at most `capacity` unread events and
+    /// drops newer ones until the receiver catches up.
+    pub fn subscribe_bounded(&self, capacity: usize) -> Receiver<SpinnerEvent> {
+        self.emitter.subscribe_bounded(capacity)
+    }
+
+    /// Calls `listener` with the next event of the given kind only. Like
+    /// `on`, it never sees events it causes while running.
+    pub fn once<F>(&mut self, event: Event, listener: F) -> Subscription
+    where
+        F: FnOnce(&SpinnerEvent) + Send + 'static,
+    {
+        self.emitter.once(event, listener)
     }
 
     pub fn start(&mut self) -> SpinnerResult<()> {
-        if self.is_running() {
-            return Err(SpinnerError::new("Spinner is already running"));
-        }
-        self.start_time = Some(Instant::now());
-        self.emitter.emit(&Event::Start.to_string(), &[]);
+        self.prepare_start()?;
         let running = self.running.clone();
         let paused = self.paused.clone();
         let mut state = self.state.clone();
-        thread::spawn(move || state.spin(running, paused));
+        let rendering = Arc::new(AtomicBool::new(true));
+        let thread_rendering = rendering.clone();
+        self.running.store(true, Ordering::SeqCst);
+        self.handle = Some(thread::spawn(move || {
+            let result = registry::rendering(state.id(), || state.spin(running, paused));
+            thread_rendering.store(false, Ordering::SeqCst);
+            result
+        }));
+        self.register(Arc::new(ActiveLine {
+            state: self.state.clone(),
+            running: self.running.clone(),
+            paused: self.paused.clone(),
+            rendering,
+        }));
+        Ok(())
+    }
+
+    /// Starts the spinner on a tokio task instead of a thread. Must be called
+    /// from within a tokio runtime.
+    #[cfg(feature = "async")]
+    pub fn start_async(&mut self) -> SpinnerResult<()> {
+        let runtime = tokio::runtime::Handle::try_current()
+            .map_err(|e| SpinnerError::Runtime(e.to_string()))?;
+        self.prepare_start()?;
         self.running.store(true, Ordering::SeqCst);
+        let (task, line) = RenderTask::spawn(
+            &runtime,
+            self.state.clone(),
+            self.running.clone(),
+            self.paused.clone(),
+        );
+        self.task = Some(task);
+        self.register(line);
+        Ok(())
+    }
+
+    fn prepare_start(&mut self) -> SpinnerResult<()> {
+        if self.is_running() {
+            return Err(SpinnerError::AlreadyRunning);
+        }
+        if let Some(id) = self.registration.take() {
+            ACTIVE_SPINNERS.unregister(id);
+        }
+        self.start_time = Some(self.clock.now());
+        self.stop_time = None;
+        self.pause_start_time = None;
+        self.pause_elapsed = Duration::from_secs(0);
+        self.emitter.emit(&SpinnerEvent::Start);
         Ok(())
     }
 
+    fn register(&mut self, line: Arc<dyn ActiveSpinner>) {
+        self.registration = Some(ACTIVE_SPINNERS.register(line.clone()));
+        self.line = Some(line);
+    }
+
     pub fn on_start<F>(&mut self, mut listener: F)
     where
-        F: FnMut() + Sync + Send + 'static,
+        F: FnMut() + Send + 'static,
     {
-        let callback = move |_: &[Box<dyn std::any::Any>]| {
-            listener();
-        };
-
-        self.emitter.on(&Event::Start.to_string(), callback);
+        self.emitter.on(Event::Start, move |_| listener()).detach();
     }
 
     pub fn stop(&mut self) -> SpinnerResult<()> {
         if !self.is_running() {
-            return Err(SpinnerError::new("Spinner is not running"));
+            return Err(SpinnerError::NotRunning);
         }
         self.state.stop()?;
-        self.running.store(false, Ordering::SeqCst);
+        self.halt()
+    }
 
-        let mut elapsed = Duration::from_secs(0);
-        if let Some(start_time) = self.start_time {
-            elapsed = start_time.elapsed();
+    /// Stops the spinner and replaces its line with `symbol` followed by
+    /// `message`, leaving that final line in the scrollback.
+    pub fn stop_with<S, T, U>(&mut self, symbol: S, message: T, color: U) -> SpinnerResult<()>
+    where
+        S: Into<String>,
+        T: Into<String>,
+        U: Into<Option<Color>>,
+    {
+        self.finish(symbol, message, color, Outcome::Custom)
+    }
+
+    fn finish<S, T, U>(
+        &mut self,
+        symbol: S,
+        message: T,
+        color: U,
+        outcome: Outcome,
+    ) -> SpinnerResult<()>
+    where
+        S: Into<String>,
+        T: Into<String>,
+        U: Into<Option<Color>>,
+    {
+        if !self.is_running() {
+            return Err(SpinnerError::NotRunning);
         }
+        let finish = FinishMessage {
+            symbol: symbol.into(),
+            text: message.into(),
+            color: color.into(),
+        };
+        self.state.finish(finish, outcome)?;
+        self.halt()
+    }
 
-        self.start_time = None;
+    pub fn succeed<T>(&mut self, message: T) -> SpinnerResult<()>
+    where
+        T: Into<String>,
+    {
+        let config = self.state.config();
+        self.finish(
+            config.symbols.success(),
+            message,
+            Color::Green,
+            Outcome::Success,
+        )
+    }
 
-        self.emitter
-            .emit(&Event::Stop.to_string(), &[Box::new(elapsed)]);
-        Ok(())
+    pub fn fail<T>(&mut self, message: T) -> SpinnerResult<()>
+    where
+        T: Into<String>,
+    {
+        let config = self.state.config();
+        self.finish(
+            config.symbols.failure(),
+            message,
+            Color::Red,
+            Outcome::Failure,
+        )
     }
 
-    pub fn on_stop<F>(&mut self, mut listener: F)
+    pub fn warn<T>(&mut self, message: T) -> SpinnerResult<()>
     where
-        F: FnMut(Duration) + Sync + Send + 'static,
+        T: Into<String>,
     {
-        let callback = move |args: &[Box<dyn std::any::Any>]| {
-            if let Some(arg) = args.first() {
-                if let Some(duration) = arg.downcast_ref::<Duration>() {
-                    listener(*duration);
-                }
+        let config = self.state.config();
+        self.finish(
+            config.symbols.warning(),
+            message,
+            Color::Yellow,
+            Outcome::Warning,
+        )
+    }
+
+    pub fn info<T>(&mut self, message: T) -> SpinnerResult<()>
+    where
+        T: Into<String>,
+    {
+        let config = self.state.config();
+        self.finish(config.symbols.info(), message, Color::Blue, Outcome::Info)
+    }
+
+    /// Calls `listener` with the symbol and message a spinner finished with.
+    /// Spinners stopped without a result are skipped.
+    pub fn on_finish<F>(&mut self, mut listener: F)
+    where
+        F: FnMut(&str, &str) + Send + 'static,
+    {
+        self.emitter
+            .on(Event::Finish, move |event| match event {
+                SpinnerEvent::Finish {
+                    outcome: Outcome::Cancelled,
+                    ..
+                } => {}
+                SpinnerEvent::Finish {
+                    symbol, message, ..
+                } => listener(symbol, message),
+                _ => {}
+            })
+            .detach();
+    }
+
+    fn halt(&mut self) -> SpinnerResult<()> {
+        self.running.store(false, Ordering::SeqCst);
+        self.clock.wake();
+
+        // Wake a paused render thread so it can observe the stop.
+        {
+            let (lock, cvar) = &*self.paused;
+            *lock.lock().unwrap() = false;
+            cvar.notify_one();
+        }
+        let stop_time = self.clock.now();
+        if let Some(pause_start_time) = self.pause_start_time.take() {
+            self.pause_elapsed += stop_time.saturating_duration_since(pause_start_time);
+        }
+        self.stop_time = Some(stop_time);
+
+        let result = match self.handle.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err(SpinnerError::ThreadPanicked),
+            None => Ok(()),
+        };
+        #[cfg(feature = "async")]
+        let result = match self.task.take() {
+            Some(task) => task.finish(),
+            None => result,
+        };
+
+        if let Some(id) = self.registration.take() {
+            ACTIVE_SPINNERS.unregister(id);
+        }
+
+        self.emitter.emit(&SpinnerEvent::Stop {
+            elapsed: self.elapsed(),
+        });
+        result
+    }
+
+    pub(crate) fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    /// Time since the spinner was last started, up to when it was stopped.
+    pub fn elapsed(&self) -> Duration {
+        match self.start_time {
+            Some(start_time) => self
+                .stop_time
+                .unwrap_or_else(|| self.clock.now())
+                .saturating_duration_since(start_time),
+            None => Duration::from_secs(0),
+        }
+    }
+
+    /// Like `elapsed`, but without the time spent paused.
+    pub fn active_elapsed(&self) -> Duration {
+        let paused = match self.pause_start_time {
+            Some(pause_start_time) => {
+                self.pause_elapsed + self.clock.now().saturating_duration_since(pause_start_time)
             }
+            None => self.pause_elapsed,
         };
+        self.elapsed().saturating_sub(paused)
+    }
 
-        self.emitter.on(&Event::Stop.to_string(), callback);
+    pub fn on_stop<F>(&mut self, mut listener: F)
+    where
+        F: FnMut(Duration) + Send + 'static,
+    {
+        self.emitter
+            .on(Event::Stop, move |event| {
+                if let SpinnerEvent::Stop { elapsed } = event {
+                    listener(*elapsed);
+                }
+            })
+            .detach();
     }
 
     pub fn pause(&mut self) -> SpinnerResult<()> {
         if !self.is_running() {
-            return Err(SpinnerError::new("Spinner is not running"));
+            return Err(SpinnerError::NotRunning);
         }
         let (lock, cvar) = &*self.paused;
         let mut paused = lock.lock().unwrap();
         if *paused {
-            return Err(SpinnerError::new("Spinner is already paused"));
+            return Err(SpinnerError::AlreadyPaused);
         }
         *paused = true;
         cvar.notify_one();
 
-        self.pause_start_time = Some(Instant::now());
-
-        let elapsed = self.start_time.map(|start_time| start_time.elapsed());
+        self.pause_start_time = Some(self.clock.now());
 
-        self.emitter
-            .emit(&Event::Pause.to_string(), &[Box::new(elapsed)]);
+        self.emitter.emit(&SpinnerEvent::Pause {
+            elapsed: self.elapsed(),
+        });
         Ok(())
     }
 
     pub fn on_pause<F>(&mut self, mut listener: F)
     where
-        F: FnMut(Option<Duration>) + Sync + Send + 'static,
+        F: FnMut(Option<Duration>) + Send + 'static,
     {
-        let callback = move |args: &[Box<dyn std::any::Any>]| {
-            if let Some(arg) = args.first() {
-                if let Some(duration) = arg.downcast_ref::<Option<Duration>>() {
-                    listener(*duration);
+        self.emitter
+            .on(Event::Pause, move |event| {
+                if let SpinnerEvent::Pause { elapsed } = event {
+                    listener(Some(*elapsed));
                 }
-            }
-        };
-
-        self.emitter.on(&Event::Pause.to_string(), callback);
+            })
+            .detach();
     }
 
     pub fn resume(&mut self) -> SpinnerResult<()> {
         if !self.is_running() {
-            return Err(SpinnerError::new("Spinner is not running"));
+            return Err(SpinnerError::NotRunning);
         }
         let (lock, cvar) = &*self.paused;
         let mut paused = lock.lock().unwrap();
         if !*paused {
-            return Err(SpinnerError::new("Spinner is not paused"));
+            return Err(SpinnerError::NotPaused);
         }
         *paused = false;
         cvar.notify_one();
 
         if let Some(pause_elapsed) = self.pause_start_time {
-            self.pause_elapsed += pause_elapsed.elapsed();
+            self.pause_elapsed += self.clock.now().saturating_duration_since(pause_elapsed);
         }
 
         self.pause_start_time = None;
 
-        self.emitter
-            .emit(&Event::Resume.to_string(), &[Box::new(self.pause_elapsed)]);
+        self.emitter.emit(&SpinnerEvent::Resume {
+            paused: self.pause_elapsed,
+        });
         Ok(())
     }
 
     pub fn on_resume<F>(&mut self, mut listener: F)
     where
-        F: FnMut(Duration) + Sync + Send + 'static,
+        F: FnMut(Duration) + Send + 'static,
     {
-        let callback = move |args: &[Box<dyn std::any::Any>]| {
-            if let Some(arg) = args.first() {
-                if let Some(duration) = arg.downcast_ref::<Duration>() {
-                    listener(*duration);
+        self.emitter
+            .on(Event::Resume, move |event| {
+                if let SpinnerEvent::Resume { paused } = event {
+                    listener(*paused);
                 }
-            }
-        };
+            })
+            .detach();
+    }
+
+    /// Clears the spinner line, runs `f` and redraws the spinner afterwards,
+    /// so that anything `f` prints stays above the spinner.
+    pub fn suspend<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        match &self.line {
+            Some(line) => registry::suspend_with(std::slice::from_ref(line), f),
+            None => f(),
+        }
+    }
 
-        self.emitter.on(&Event::Resume.to_string(), callback);
+    pub fn println<T>(&mut self, message: T) -> SpinnerResult<()>
+    where
+        T: AsRef<str>,
+    {
+        let output = self.state.output();
+        self.suspend(|| {
+            let mut w = output.lock().unwrap();
+            writeln!(w, "{}", message.as_ref())?;
+            w.flush()
+        })
+        .map_err(SpinnerError::Io)
     }
 
     pub fn is_running(&self) -> bool {
@@ -218,8 +509,11 @@ impl Spinner {
         self.state.update(UpdateMessage::Message(message.into()))
     }
 
-    pub fn set_style(&mut self, style: impl Into<SpinnerStyle>) -> SpinnerResult<()> {
-        self.state.update(UpdateMessage::Style(style.into()))
+    /// Accepts a builtin `SpinnerStyle` or the name of a registered style.
+    pub fn set_style(&mut self, style: impl Into<StyleRef>) -> SpinnerResult<()> {
+        let style = style.into();
+        let data = style.resolve()?;
+        self.state.update(UpdateMessage::Style(style, data))
     }
 
     pub fn set_color_scheme<U>(
@@ -238,6 +532,14 @@ impl Spinner {
         ))
     }
 
+    pub fn set_color_policy<T>(&mut self, color_policy: T) -> SpinnerResult<()>
+    where
+        T: Into<ColorPolicy>,
+    {
+        self.state
+            .update(UpdateMessage::ColorPolicy(color_policy.into()))
+    }
+
     pub fn set_reverse(&mut self, reverse: bool) -> SpinnerResult<()> {
         self.state.set_reverse(reverse);
         Ok(())
@@ -255,15 +557,18 @@ impl Spinner {
     where
         V: Into<f64>,
     {
-        self.state
-            .update(UpdateMessage::FramesPerSecond(fps.into()))
+        let fps = fps.into();
+        validate_rate("fps", fps)?;
+        self.state.update(UpdateMessage::FramesPerSecond(fps))
     }
 
     pub fn set_speed<V>(&mut self, rpm: V) -> SpinnerResult<()>
     where
         V: Into<f64>,
     {
-        self.state.update(UpdateMessage::Speed(rpm.into()))
+        let rpm = rpm.into();
+        validate_rate("speed", rpm)?;
+        self.state.update(UpdateMessage::Speed(rpm))
     }
 
     pub fn set_frames<S>(&mut self, frames: &[S]) -> SpinnerResult<()>
@@ -271,30 +576,95 @@ impl Spinner {
         S: AsRef<str>,
     {
         let frames: Vec<String> = frames.iter().map(|s| s.as_ref().to_string()).collect();
+        validate_frames(&frames)?;
         self.state.update(UpdateMessage::Frames(frames))
     }
 
     pub fn set_output_stream<S>(&mut self, stream: S) -> SpinnerResult<()>
     where
-        S: Into<SpinnerStream>,
+        S: TryInto<SpinnerStream>,
+        S::Error: Into<SpinnerError>,
     {
-        self.state.update(UpdateMessage::Stream(stream.into()))
+        let stream = stream.try_into().map_err(Into::into)?;
+        self.state.update(UpdateMessage::Stream(stream))
+    }
+
+    pub fn set_render_mode<T>(&mut self, render_mode: T) -> SpinnerResult<()>
+    where
+        T: Into<RenderMode>,
+    {
+        self.state
+            .update(UpdateMessage::RenderMode(render_mode.into()))
+    }
+
+    pub fn set_template(&mut self, template: &str) -> SpinnerResult<()> {
+        let template = Template::parse(template)?;
+        self.state.update(UpdateMessage::Template(Some(template)))
+    }
+
+    pub fn clear_template(&mut self) -> SpinnerResult<()> {
+        self.state.update(UpdateMessage::Template(None))
+    }
+
+    /// Shows the time spent running, without pauses, at the end of the
+    /// default line. Templates use the `{elapsed}` placeholder instead.
+    pub fn set_show_elapsed(&mut self, show_elapsed: bool) -> SpinnerResult<()> {
+        self.state.update(UpdateMessage::ShowElapsed(show_elapsed))
+    }
+
+    /// Emits an `Event::Frame` with the frame index on every tick. Off by
+    /// default, since it fires at the frame rate: a receiver from `subscribe`
+    /// that is not read keeps growing by one event per frame, so prefer
+    /// `subscribe_bounded` when frame events are on.
+    pub fn set_frame_events(&mut self, frame_events: bool) -> SpinnerResult<()> {
+        self.state.update(UpdateMessage::FrameEvents(frame_events))
+    }
+
+    pub fn set_prefix<T>(&mut self, prefix: T) -> SpinnerResult<()>
+    where
+        T: Into<String>,
+    {
+        self.state.update(UpdateMessage::Prefix(prefix.into()))
+    }
+
+    pub fn set_total(&mut self, total: u64) -> SpinnerResult<()> {
+        self.state
+            .update(UpdateMessage::Progress(ProgressUpdate::Total(total)))
+    }
+
+    pub fn inc(&mut self, delta: u64) -> SpinnerResult<()> {
+        self.state
+            .update(UpdateMessage::Progress(ProgressUpdate::Inc(delta)))
+    }
+
+    pub fn set_position(&mut self, position: u64) -> SpinnerResult<()> {
+        self.state
+            .update(UpdateMessage::Progress(ProgressUpdate::Position(position)))
+    }
+
+    pub fn set_progress_unit(&mut self, unit: ProgressUnit) -> SpinnerResult<()> {
+        self.state
+            .update(UpdateMessage::Progress(ProgressUpdate::Unit(unit)))
     }
 }
 
 impl Drop for Spinner {
     fn drop(&mut self) {
         if self.is_running() {
-            self.stop().unwrap();
+            let _ = self.stop();
+        } else if let Some(id) = self.registration.take() {
+            ACTIVE_SPINNERS.unregister(id);
         }
     }
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use crossbeam::channel::unbounded;
 
     use super::*;
+    use crate::testing::CaptureWriter;
 
     #[test]
     fn test_new() {
@@ -594,6 +964,677 @@ mod tests {
         assert!(spinner.stop().is_ok());
     }
 
+    #[test]
+    fn test_succeed_stops_spinner() {
+        let mut spinner = Spinner::new("Loading ...");
+        spinner.start().unwrap();
+        assert!(spinner.succeed("Done").is_ok());
+        assert!(!spinner.is_running());
+    }
+
+    #[test]
+    fn test_finish_stopped_spinner() {
+        let mut spinner = Spinner::new("Loading ...");
+        assert!(spinner.succeed("Done").is_err());
+        assert!(spinner.fail("Failed").is_err());
+        assert!(spinner.warn("Careful").is_err());
+        assert!(spinner.info("Note").is_err());
+    }
+
+    #[test]
+    fn test_finish_emits_finish_and_stop_events() {
+        let mut spinner = Spinner::new("Loading");
+        let (finish_tx, finish_rx) = unbounded();
+        let (stop_tx, stop_rx) = unbounded();
+
+        spinner.on_finish(move |symbol, message| {
+            finish_tx
+                .send((symbol.to_string(), message.to_string()))
+                .unwrap();
+        });
+        spinner.on_stop(move |elapsed| {
+            stop_tx.send(elapsed).unwrap();
+        });
+
+        spinner.start().unwrap();
+        spinner.fail("Failed").unwrap();
+
+        let (symbol, message) = finish_rx.recv().unwrap();
+        assert_eq!(symbol, FAILURE_SYMBOL);
+        assert_eq!(message, "Failed");
+        assert!(stop_rx.recv().is_ok());
+    }
+
+    #[test]
+    fn test_stop_with_custom_symbol() {
+        let mut spinner = Spinner::new("Loading");
+        let (tx, rx) = unbounded();
+        spinner.on_finish(move |symbol, _| {
+            tx.send(symbol.to_string()).unwrap();
+        });
+        spinner.start().unwrap();
+        spinner.stop_with("→", "Skipped", None).unwrap();
+        assert_eq!(rx.recv().unwrap(), "→");
+    }
+
+    #[test]
+    fn test_stop_paused_spinner() {
+        let mut spinner = Spinner::new("Loading ...");
+        spinner.start().unwrap();
+        spinner.pause().unwrap();
+        assert!(spinner.stop().is_ok());
+        assert!(!*spinner.paused.0.lock().unwrap());
+    }
+
+    #[test]
+    fn test_switch_to_progress_mode() {
+        let mut spinner = Spinner::new("Downloading");
+        spinner.start().unwrap();
+        assert!(spinner.inc(10).is_ok());
+        assert!(spinner.set_total(100).is_ok());
+        assert!(spinner.set_position(50).is_ok());
+        assert!(spinner.stop().is_ok());
+    }
+
+    #[test]
+    fn test_set_template() {
+        let mut spinner = Spinner::new("Loading");
+        assert!(spinner
+            .set_template("{spinner} [{elapsed}] {msg:.cyan}{dots} {prefix}")
+            .is_ok());
+        assert!(spinner.set_prefix("[1/3]").is_ok());
+        assert!(spinner.clear_template().is_ok());
+    }
+
+    #[test]
+    fn test_set_invalid_template() {
+        let mut spinner = Spinner::new("Loading");
+        let result = spinner.set_template("{spinner} {unknown}");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Unknown template placeholder: unknown"
+        );
+    }
+
+    #[test]
+    fn test_stop_joins_render_thread() {
+        let mut spinner = Spinner::new("Loading ...");
+        spinner.start().unwrap();
+        assert!(spinner.handle.is_some());
+        spinner.stop().unwrap();
+        assert!(spinner.handle.is_none());
+    }
+
+    #[test]
+    fn test_restart_after_stop() {
+        let mut spinner = Spinner::new("Loading ...");
+        spinner.start().unwrap();
+        spinner.stop().unwrap();
+        spinner.start().unwrap();
+        thread::sleep(Duration::from_millis(100));
+        assert!(!spinner.handle.as_ref().unwrap().is_finished());
+        spinner.stop().unwrap();
+    }
+
+    #[test]
+    fn test_interrupt_stops_render_thread() {
+        let mut spinner = Spinner::new("Loading ...");
+        let (tx, rx) = unbounded();
+        let _subscription = spinner.on(Event::Finish, move |event| {
+            tx.send(event.clone()).unwrap();
+        });
+        spinner.start().unwrap();
+        spinner.line.clone().unwrap().interrupt(Some("Interrupted"));
+        assert!(!spinner.is_running());
+        assert!(spinner.handle.take().unwrap().join().unwrap().is_ok());
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![SpinnerEvent::Finish {
+                symbol: FAILURE_SYMBOL.to_string(),
+                message: "Interrupted".to_string(),
+                outcome: Outcome::Cancelled,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_set_render_mode() {
+        let mut spinner = Spinner::new("Loading ...");
+        assert!(spinner.set_render_mode(RenderMode::Plain).is_ok());
+        spinner.start().unwrap();
+        assert!(spinner.set_render_mode("hidden").is_ok());
+        assert!(spinner.succeed("Done").is_ok());
+    }
+
+    #[test]
+    fn test_set_color_policy() {
+        let mut spinner = Spinner::new("Loading ...");
+        assert!(spinner.set_color_policy(ColorPolicy::Never).is_ok());
+        assert!(spinner.set_color_policy("always").is_ok());
+    }
+
+    #[test]
+    fn test_set_invalid_output_stream() {
+        let mut spinner = Spinner::new("Loading ...");
+        assert!(spinner.set_output_stream("stderr").is_ok());
+        assert!(spinner.set_output_stream("invalid").is_err());
+    }
+
+    #[test]
+    fn test_render_into_custom_writer() {
+        let buffer = CaptureWriter::new();
+        let mut spinner = Spinner::new("Working");
+        spinner.set_output_stream(buffer.clone()).unwrap();
+        spinner.start().unwrap();
+        spinner.succeed("Done").unwrap();
+
+        let output = buffer.contents();
+        assert!(output.ends_with("✔ Done\n"));
+        assert!(!output.contains('\x1B'));
+    }
+
+    #[test]
+    fn test_println_when_stopped() {
+        let buffer = CaptureWriter::new();
+        let mut spinner = Spinner::new("Working");
+        spinner.set_output_stream(buffer.clone()).unwrap();
+        spinner.start().unwrap();
+        spinner.stop().unwrap();
+
+        spinner.println("warning: disk almost full").unwrap();
+        assert!(buffer.contents().ends_with("warning: disk almost full\n"));
+    }
+
+    #[test]
+    fn test_println_clears_and_redraws_spinner() {
+        let buffer = CaptureWriter::new();
+        let mut spinner = Spinner::new("Working");
+        spinner.set_output_stream(buffer.clone()).unwrap();
+        spinner.set_render_mode(RenderMode::Animated).unwrap();
+        spinner.start().unwrap();
+        thread::sleep(Duration::from_millis(100));
+
+        spinner.println("warning: disk almost full").unwrap();
+        thread::sleep(Duration::from_millis(100));
+        spinner.stop().unwrap();
+
+        let output = buffer.contents();
+        let (before, after) = output.split_once("warning: disk almost full\n").unwrap();
+        assert!(before.contains("Working") && before.ends_with("\r\x1B[K"));
+        assert!(after.contains("Working"));
+    }
+
+    #[test]
+    fn test_listener_can_suspend_its_own_spinner() {
+        let buffer = CaptureWriter::new();
+        let mut spinner = Spinner::new("Working");
+        spinner.set_output_stream(buffer.clone()).unwrap();
+        spinner.set_render_mode(RenderMode::Animated).unwrap();
+        let (printed, printed_rx) = unbounded();
+        let mut output = buffer.clone();
+        let _subscription = spinner.on(Event::MessageChange, move |_| {
+            crate::suspend(|| writeln!(output, "log line").unwrap());
+            let _ = printed.send(());
+        });
+        spinner.start().unwrap();
+        thread::sleep(Duration::from_millis(50));
+
+        spinner.set_message("Still working").unwrap();
+        assert!(printed_rx.recv_timeout(Duration::from_secs(2)).is_ok());
+        thread::sleep(Duration::from_millis(100));
+        spinner.stop().unwrap();
+
+        let output = buffer.contents();
+        let (_, after) = output.split_once("\r\x1B[Klog line\n").unwrap();
+        assert!(after.contains("Still working"));
+    }
+
+    #[test]
+    fn test_suspend_returns_closure_result() {
+        let mut spinner = Spinner::new("Working");
+        spinner.set_render_mode(RenderMode::Hidden).unwrap();
+        assert_eq!(spinner.suspend(|| 42), 42);
+
+        spinner.start().unwrap();
+        assert_eq!(spinner.suspend(|| "done"), "done");
+        spinner.pause().unwrap();
+        assert_eq!(spinner.suspend(|| 7), 7);
+        spinner.resume().unwrap();
+        spinner.stop().unwrap();
+    }
+
+    #[test]
+    fn test_elapsed_before_start() {
+        let spinner = Spinner::new("Loading ...");
+        assert_eq!(spinner.elapsed(), Duration::from_secs(0));
+        assert_eq!(spinner.active_elapsed(), Duration::from_secs(0));
+    }
+
+    #[test]
+    fn test_active_elapsed_excludes_pauses() {
+        let mut spinner = Spinner::new("Loading ...");
+        spinner.set_render_mode(RenderMode::Hidden).unwrap();
+        spinner.start().unwrap();
+        thread::sleep(Duration::from_millis(50));
+        spinner.pause().unwrap();
+        thread::sleep(Duration::from_millis(100));
+        assert!(spinner.elapsed() >= Duration::from_millis(150));
+        assert!(spinner.active_elapsed() < Duration::from_millis(100));
+
+        spinner.resume().unwrap();
+        spinner.pause().unwrap();
+        spinner.stop().unwrap();
+
+        let elapsed = spinner.elapsed();
+        let active_elapsed = spinner.active_elapsed();
+        thread::sleep(Duration::from_millis(20));
+        assert_eq!(spinner.elapsed(), elapsed);
+        assert_eq!(spinner.active_elapsed(), active_elapsed);
+        assert!(active_elapsed + Duration::from_millis(100) <= elapsed);
+    }
+
+    #[test]
+    fn test_restart_resets_elapsed() {
+        let mut spinner = Spinner::new("Loading ...");
+        spinner.set_render_mode(RenderMode::Hidden).unwrap();
+        spinner.start().unwrap();
+        spinner.pause().unwrap();
+        thread::sleep(Duration::from_millis(50));
+        spinner.stop().unwrap();
+
+        spinner.start().unwrap();
+        assert!(spinner.set_show_elapsed(true).is_ok());
+        spinner.stop().unwrap();
+        assert!(spinner.elapsed() < Duration::from_millis(50));
+        assert_eq!(spinner.active_elapsed(), spinner.elapsed());
+    }
+
+    #[test]
+    fn test_setters_validate_frames_and_rates() {
+        let mut spinner = Spinner::new("Loading ...");
+        let frames: [&str; 0] = [];
+        assert!(spinner.set_frames(&frames).is_err());
+        assert!(spinner.set_fps(0).is_err());
+        assert!(spinner.set_speed(-1.0).is_err());
+        assert!(spinner.set_fps(f64::INFINITY).is_err());
+        assert!(spinner.set_fps(30).is_ok());
+    }
+
+    #[test]
+    fn test_set_style_by_name() {
+        let mut spinner = Spinner::new("Loading ...");
+        styles::register_style(
+            "test-set-style",
+            builtins::SpinnerData {
+                frames: vec!["+".to_string(), "x".to_string()],
+                frame_duration: 100,
+            },
+        )
+        .unwrap();
+        assert!(spinner.set_style("test-set-style").is_ok());
+        assert!(spinner.set_style(builtins::SpinnerStyle::Dots).is_ok());
+        assert!(spinner.set_style("NoSuchStyle").is_err());
+    }
+
+    #[test]
+    fn test_error_kinds() {
+        let mut spinner = Spinner::new("Loading ...");
+        spinner.set_render_mode(RenderMode::Hidden).unwrap();
+        assert!(matches!(spinner.pause(), Err(SpinnerError::NotRunning)));
+        assert!(matches!(spinner.stop(), Err(SpinnerError::NotRunning)));
+
+        spinner.start().unwrap();
+        assert!(matches!(spinner.start(), Err(SpinnerError::AlreadyRunning)));
+        assert!(matches!(spinner.resume(), Err(SpinnerError::NotPaused)));
+        spinner.pause().unwrap();
+        assert!(matches!(spinner.pause(), Err(SpinnerError::AlreadyPaused)));
+        assert!(matches!(
+            spinner.set_style("dotz"),
+            Err(SpinnerError::UnknownStyle { .. })
+        ));
+        assert!(matches!(
+            spinner.set_fps(0),
+            Err(SpinnerError::InvalidRate { .. })
+        ));
+        spinner.stop().unwrap();
+    }
+
+    fn hidden_spinner(message: &str) -> Spinner {
+        let mut spinner = Spinner::new(message);
+        spinner.set_render_mode(RenderMode::Hidden).unwrap();
+        spinner
+    }
+
+    #[test]
+    fn test_message_change_event() {
+        let mut spinner = hidden_spinner("Loading ...");
+        let (tx, rx) = unbounded();
+        let _subscription = spinner.on(Event::MessageChange, move |event| {
+            tx.send(event.clone()).unwrap();
+        });
+
+        spinner.set_message("Compiling").unwrap();
+        spinner.set_message("Linking").unwrap();
+        // Changes are emitted once the render thread applies them.
+        assert!(rx.try_recv().is_err());
+        spinner.start().unwrap();
+        spinner.stop().unwrap();
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            SpinnerEvent::MessageChange {
+                old: "Loading ...".to_string(),
+                new: "Compiling".to_string(),
+            }
+        );
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            SpinnerEvent::MessageChange {
+                old: "Compiling".to_string(),
+                new: "Linking".to_string(),
+            }
+        );
+    }
+
+    #[test]
+    fn test_listener_setting_the_message_is_not_called_again() {
+        let mut spinner = hidden_spinner("Loading");
+        let mut handle = Spinner::with_state(spinner.state.clone());
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let listener_calls = calls.clone();
+        let _subscription = spinner.on(Event::MessageChange, move |_| {
+            listener_calls.fetch_add(1, Ordering::SeqCst);
+            handle.set_message("Still loading").unwrap();
+        });
+        let receiver = spinner.subscribe();
+
+        spinner.set_message("Compiling").unwrap();
+        spinner.start().unwrap();
+        thread::sleep(Duration::from_millis(100));
+        spinner.stop().unwrap();
+
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        let changes: Vec<_> = receiver
+            .try_iter()
+            .filter(|event| matches!(event, SpinnerEvent::MessageChange { .. }))
+            .collect();
+        assert_eq!(changes.len(), 2);
+    }
+
+    #[test]
+    fn test_style_change_event() {
+        let mut spinner = hidden_spinner("Loading ...");
+        let (tx, rx) = unbounded();
+        let _subscription = spinner.on(Event::StyleChange, move |event| {
+            tx.send(event.clone()).unwrap();
+        });
+
+        spinner.start().unwrap();
+        spinner.set_style(builtins::SpinnerStyle::Line).unwrap();
+        assert!(spinner.set_style("dotz").is_err());
+        spinner.stop().unwrap();
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            SpinnerEvent::StyleChange {
+                style: StyleRef::Builtin(builtins::SpinnerStyle::Line),
+            }
+        );
+        assert!(rx.try_recv().is_err());
+    }
+
+    #[test]
+    fn test_appearance_change_events() {
+        let mut spinner = hidden_spinner("Loading ...");
+        let (tx, rx) = unbounded();
+        for event in [
+            Event::FramesChange,
+            Event::ColorChange,
+            Event::AlignmentChange,
+        ] {
+            let tx = tx.clone();
+            spinner
+                .on(event, move |event| tx.send(event.clone()).unwrap())
+                .detach();
+        }
+
+        spinner.start().unwrap();
+        spinner.set_frames(&["a", "b"]).unwrap();
+        spinner
+            .set_color_scheme(Some(Color::Red), None, Some(Color::Blue))
+            .unwrap();
+        spinner.set_alignment(Alignment::Right).unwrap();
+        spinner.stop().unwrap();
+
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![
+                SpinnerEvent::FramesChange {
+                    frames: vec!["a".to_string(), "b".to_string()],
+                },
+                SpinnerEvent::ColorChange {
+                    spinner: Some(Color::Red),
+                    message: None,
+                    dots: Some(Color::Blue),
+                },
+                SpinnerEvent::AlignmentChange {
+                    alignment: Alignment::Right,
+                },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_frame_events_are_opt_in() {
+        let mut spinner = Spinner::builder("Loading ...")
+            .frames(&["a", "b", "c"])
+            .fps(100)
+            .render_mode(RenderMode::Hidden)
+            .build()
+            .unwrap();
+        let (tx, rx) = unbounded();
+        let _subscription = spinner.on(Event::Frame, move |event| {
+            tx.send(event.clone()).unwrap();
+        });
+
+        spinner.start().unwrap();
+        thread::sleep(Duration::from_millis(100));
+        spinner.stop().unwrap();
+        assert!(rx.try_recv().is_err());
+
+        spinner.set_frame_events(true).unwrap();
+        spinner.start().unwrap();
+        thread::sleep(Duration::from_millis(100));
+        spinner.stop().unwrap();
+
+        let frames: Vec<_> = rx.try_iter().take(4).collect();
+        assert_eq!(
+            frames,
+            ["a", "b", "c", "a"]
+                .iter()
+                .enumerate()
+                .map(|(index, frame)| SpinnerEvent::Frame {
+                    index: index % 3,
+                    frame: frame.to_string(),
+                })
+                .collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_finish_outcomes() {
+        let mut spinner = hidden_spinner("Loading");
+        let (tx, rx) = unbounded();
+        let _subscription = spinner.on(Event::Finish, move |event| {
+            if let SpinnerEvent::Finish { outcome, .. } = event {
+                tx.send(*outcome).unwrap();
+            }
+        });
+
+        let finishes: [fn(&mut Spinner) -> SpinnerResult<()>; 6] = [
+            |spinner| spinner.succeed("Done"),
+            |spinner| spinner.fail("Failed"),
+            |spinner| spinner.warn("Careful"),
+            |spinner| spinner.info("Note"),
+            |spinner| spinner.stop_with("→", "Skipped", None),
+            |spinner| spinner.stop(),
+        ];
+        for finish in finishes {
+            spinner.start().unwrap();
+            finish(&mut spinner).unwrap();
+        }
+
+        assert_eq!(
+            rx.try_iter().collect::<Vec<_>>(),
+            vec![
+                Outcome::Success,
+                Outcome::Failure,
+                Outcome::Warning,
+                Outcome::Info,
+                Outcome::Custom,
+                Outcome::Cancelled,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_cancelled_finish_keeps_message() {
+        let mut spinner = hidden_spinner("Loading");
+        let (tx, rx) = unbounded();
+        let _subscription = spinner.on(Event::Finish, move |event| {
+            tx.send(event.clone()).unwrap();
+        });
+        let (finish_tx, finish_rx) = unbounded();
+        spinner.on_finish(move |_, _| finish_tx.send(()).unwrap());
+
+        spinner.start().unwrap();
+        spinner.set_message("Compiling").unwrap();
+        spinner.stop().unwrap();
+
+        assert_eq!(
+            rx.try_recv().unwrap(),
+            SpinnerEvent::Finish {
+                symbol: String::new(),
+                message: "Compiling".to_string(),
+                outcome: Outcome::Cancelled,
+            }
+        );
+        assert!(finish_rx.try_recv().is_err());
+    }
+
+    struct FailingWriter;
+
+    impl Write for FailingWriter {
+        fn write(&mut self, _: &[u8]) -> std::io::Result<usize> {
+            Err(std::io::ErrorKind::BrokenPipe.into())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_render_error_event() {
+        let mut spinner = Spinner::new("Loading");
+        spinner
+            .set_output_stream(SpinnerStream::custom(FailingWriter))
+            .unwrap();
+        spinner.set_render_mode(RenderMode::Animated).unwrap();
+        let (tx, rx) = unbounded();
+        let _subscription = spinner.on(Event::Error, move |event| {
+            tx.send(event.clone()).unwrap();
+        });
+
+        spinner.start().unwrap();
+        thread::sleep(Duration::from_millis(50));
+        assert!(matches!(spinner.stop(), Err(SpinnerError::Io(_))));
+
+        let event = rx.try_recv().unwrap();
+        assert!(matches!(
+            event,
+            SpinnerEvent::Error {
+                kind: std::io::ErrorKind::BrokenPipe,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn test_listeners_only_receive_their_kind() {
+        let mut spinner = Spinner::new("Loading ...");
+        spinner.set_render_mode(RenderMode::Hidden).unwrap();
+        let (tx, rx) = unbounded();
+        let _subscription = spinner.on(Event::Resume, move |event| {
+            tx.send(event.kind()).unwrap();
+        });
+
+        spinner.start().unwrap();
+        spinner.pause().unwrap();
+        spinner.resume().unwrap();
+        spinner.stop().unwrap();
+
+        assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![Event::Resume]);
+    }
+
+    #[test]
+    fn test_subscribe_receives_events_on_another_thread() {
+        let mut spinner = hidden_spinner("Loading");
+        let events = spinner.subscribe();
+        let consumer = thread::spawn(move || events.iter().map(|event| event.kind()).collect());
+
+        spinner.start().unwrap();
+        spinner.set_message("Compiling").unwrap();
+        spinner.succeed("Done").unwrap();
+        drop(spinner);
+
+        let kinds: Vec<Event> = consumer.join().unwrap();
+        assert_eq!(
+            kinds,
+            vec![
+                Event::Start,
+                Event::MessageChange,
+                Event::Finish,
+                Event::Stop
+            ]
+        );
+    }
+
+    #[test]
+    fn test_dropped_subscription_stops_listener() {
+        let mut spinner = hidden_spinner("Loading ...");
+        let (tx, rx) = unbounded();
+        let subscription = spinner.on(Event::MessageChange, move |event| {
+            tx.send(event.clone()).unwrap();
+        });
+
+        spinner.start().unwrap();
+        spinner.set_message("Compiling").unwrap();
+        spinner.stop().unwrap();
+        drop(subscription);
+        spinner.start().unwrap();
+        spinner.set_message("Linking").unwrap();
+        spinner.stop().unwrap();
+
+        assert_eq!(rx.try_iter().count(), 1);
+    }
+
+    #[test]
+    fn test_once_listener() {
+        let mut spinner = hidden_spinner("Loading ...");
+        let (tx, rx) = unbounded();
+        let _subscription = spinner.once(Event::MessageChange, move |event| {
+            tx.send(event.clone()).unwrap();
+        });
+
+        spinner.start().unwrap();
+        spinner.set_message("Compiling").unwrap();
+        spinner.set_message("Linking").unwrap();
+        spinner.stop().unwrap();
+
+        assert_eq!(rx.try_iter().count(), 1);
+    }
+
     #[test]
     fn test_drop() {
         let mut spinner = Spinner::new("Loading ...");
@@ -0,0 +1,890 @@
+use std::borrow::Cow;
+use std::io::Write;
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc, Mutex,
+};
+use std::thread::{self, JoinHandle};
+use std::time::Duration;
+
+use colored::Color;
+
+use super::{
+    builder::{frames_per_second, validate_frames},
+    builtins::{get_spinner_data, SpinnerStyle},
+    color::{paint, strip_ansi, ColorPolicy},
+    message::FinishMessage,
+    registry::{self, lock, ActiveSpinner, SuspendGuard, ACTIVE_SPINNERS},
+    render_mode::RenderMode,
+    state::trim_trailing_dots,
+    stream::SpinnerStream,
+    styles::StyleRef,
+};
+use crate::{config::SpinnerConfig, SpinnerError, SpinnerResult};
+
+const DEFAULT_INTERVAL: Duration = Duration::from_millis(80);
+
+struct ChildLine {
+    id: usize,
+    text: String,
+    dots: String,
+    frames: Vec<String>,
+    style_color: Option<Color>,
+    text_color: Option<Color>,
+    dot_color: Option<Color>,
+    frame_index: usize,
+    dot_count: usize,
+    finish: Option<FinishMessage>,
+    announced: bool,
+    finish_printed: bool,
+}
+
+impl ChildLine {
+    fn new(id: usize, message: impl Into<String>, config: &SpinnerConfig) -> Self {
+        let (text, dots) = trim_trailing_dots(message);
+        let dot_count = dots.len();
+        let frames = match config.style_data() {
+            Some(data) => data.frames,
+            None => get_spinner_data(&SpinnerStyle::default()).frames,
+        };
+
+        Self {
+            id,
+            text,
+            dots,
+            frames,
+            style_color: config.style_color.or(Some(Color::Magenta)),
+            text_color: config.message_color,
+            dot_color: config.dots_color.or(Some(Color::Magenta)),
+            frame_index: 0,
+            dot_count,
+            finish: None,
+            announced: false,
+            finish_printed: false,
+        }
+    }
+
+    fn render(&self) -> String {
+        if let Some(finish) = &self.finish {
+            return format!("{} {}", paint(&finish.symbol, finish.color), finish.text);
+        }
+
+        let frame = &self.frames[self.frame_index % self.frames.len()];
+        let dots = ".".repeat(self.dot_count.min(self.dots.len()));
+
+        format!(
+            "{} {}{}",
+            paint(frame, self.style_color),
+            paint(&self.text, self.text_color),
+            paint(&dots, self.dot_color)
+        )
+    }
+
+    /// The line printed in plain mode when the message is new or changed, or
+    /// when the child finished, if it was not printed yet.
+    fn render_plain(&mut self) -> Option<String> {
+        match &self.finish {
+            Some(_) if self.finish_printed => None,
+            Some(finish) => {
+                self.announced = true;
+                self.finish_printed = true;
+                Some(format!("{} {}", finish.symbol, finish.text))
+            }
+            None if self.announced => None,
+            None => {
+                self.announced = true;
+                Some(format!("{}{}", self.text, self.dots))
+            }
+        }
+    }
+
+    fn advance(&mut self) {
+        if self.finish.is_some() {
+            return;
+        }
+        let frames_length = self.frames.len();
+        self.frame_index = (self.frame_index + 1) % frames_length;
+        self.dot_count = (self.dot_count + 1) % (frames_length * 4);
+    }
+}
+
+#[derive(Default)]
+struct MultiState {
+    /// Resolved when the render loop starts; `Auto` draws animated lines.
+    mode: RenderMode,
+    colors: bool,
+    config: Arc<SpinnerConfig>,
+    children: Vec<ChildLine>,
+    next_id: usize,
+    drawn_lines: usize,
+    interrupted: bool,
+    suspended: usize,
+}
+
+impl MultiState {
+    fn child_mut(&mut self, id: usize) -> SpinnerResult<&mut ChildLine> {
+        self.children
+            .iter_mut()
+            .find(|child| child.id == id)
+            .ok_or(SpinnerError::Removed)
+    }
+
+    fn render(&mut self) -> String {
+        if self.interrupted || self.suspended > 0 {
+            return String::new();
+        }
+        match self.mode {
+            RenderMode::Hidden => String::new(),
+            RenderMode::Plain => self.render_plain(),
+            RenderMode::Auto | RenderMode::Animated => self.render_animated(),
+        }
+    }
+
+    fn render_plain(&mut self) -> String {
+        let colors = self.colors;
+        self.children
+            .iter_mut()
+            .filter_map(ChildLine::render_plain)
+            .map(|line| format!("{}\n", colorize(colors, &line)))
+            .collect()
+    }
+
+    fn render_animated(&mut self) -> String {
+        let mut output = String::new();
+        if self.drawn_lines > 0 {
+            output.push_str(&format!("\x1B[{}A", self.drawn_lines));
+        }
+        for child in &self.children {
+            let line = child.render();
+            output.push_str(&format!("\r\x1B[K{}\n", colorize(self.colors, &line)));
+        }
+        // Clear whatever is left over from children that were removed.
+        output.push_str("\x1B[J");
+        self.drawn_lines = self.children.len();
+        output
+    }
+
+    /// Clears the drawn lines; rendering resumes below whatever is printed in
+    /// the meantime.
+    fn suspend(&mut self) -> String {
+        self.suspended += 1;
+        let output = match self.drawn_lines {
+            0 => String::new(),
+            lines => format!("\x1B[{}A\r\x1B[J", lines),
+        };
+        self.drawn_lines = 0;
+        output
+    }
+
+    fn interrupt(&mut self, message: Option<&str>) -> String {
+        match message {
+            Some(message) => {
+                for child in self.children.iter_mut().filter(|c| c.finish.is_none()) {
+                    child.finish = Some(FinishMessage {
+                        symbol: self.config.symbols.failure().to_string(),
+                        text: message.to_string(),
+                        color: Some(Color::Red),
+                    });
+                }
+            }
+            None => self.children.retain(|child| child.finish.is_some()),
+        }
+        let output = self.render();
+        self.interrupted = true;
+        output
+    }
+
+    fn tick(&mut self) {
+        for child in &mut self.children {
+            child.advance();
+        }
+    }
+}
+
+struct ActiveLines {
+    state: Arc<Mutex<MultiState>>,
+    output: Arc<Mutex<SpinnerStream>>,
+    running: Arc<AtomicBool>,
+}
+
+impl ActiveSpinner for ActiveLines {
+    fn interrupt(&self, message: Option<&str>) {
+        self.running.store(false, Ordering::SeqCst);
+
+        let mut state = lock(&self.state);
+        let mut frame = state.interrupt(message);
+        if state.mode == RenderMode::Animated {
+            frame.push_str("\x1B[?25h"); // show cursor
+        }
+        let mut w = lock(&self.output);
+        let _ = write!(w, "{}", frame);
+        let _ = w.flush();
+    }
+
+    fn suspend(&self) -> Option<SuspendGuard> {
+        if !self.running.load(Ordering::SeqCst) {
+            return None;
+        }
+        // The render thread writes under the state lock, so no frame can be
+        // in flight once the lines are cleared here.
+        let mut state = lock(&self.state);
+        let frame = state.suspend();
+        let mut w = lock(&self.output);
+        let _ = write!(w, "{}", frame);
+        let _ = w.flush();
+        Some(SuspendGuard::new(ResumeLines(self.state.clone())))
+    }
+}
+
+struct ResumeLines(Arc<Mutex<MultiState>>);
+
+impl Drop for ResumeLines {
+    fn drop(&mut self) {
+        lock(&self.0).suspended -= 1;
+    }
+}
+
+pub struct MultiSpinner {
+    state: Arc<Mutex<MultiState>>,
+    output: Arc<Mutex<SpinnerStream>>,
+    running: Arc<AtomicBool>,
+    color_policy: ColorPolicy,
+    render_mode: RenderMode,
+    interval: Arc<Mutex<Duration>>,
+    handle: Option<JoinHandle<SpinnerResult<()>>>,
+    registration: Option<usize>,
+}
+
+impl MultiSpinner {
+    pub fn new() -> Self {
+        Self::with_config(SpinnerConfig::global().clone())
+    }
+
+    /// Starts from `config` instead of [`SpinnerConfig::global`], so that the
+    /// end user's config file and `SPINERGY_*` variables are not consulted.
+    pub fn with_config(config: SpinnerConfig) -> Self {
+        let state = MultiState {
+            config: Arc::new(config),
+            ..MultiState::default()
+        };
+        let config = state.config.clone();
+        Self {
+            state: Arc::new(Mutex::new(state)),
+            output: Arc::new(Mutex::new(config.output_stream().unwrap_or_default())),
+            running: Arc::new(AtomicBool::new(false)),
+            color_policy: ColorPolicy::default(),
+            render_mode: RenderMode::default(),
+            interval: Arc::new(Mutex::new(
+                config
+                    .fps
+                    .and_then(|fps| frames_per_second(fps).ok())
+                    .unwrap_or(DEFAULT_INTERVAL),
+            )),
+            handle: None,
+            registration: None,
+        }
+    }
+
+    pub fn add(&self, message: impl Into<String>) -> ChildSpinner {
+        let mut state = self.state.lock().unwrap();
+        let id = state.next_id;
+        state.next_id += 1;
+        let child = ChildLine::new(id, message, &state.config);
+        state.children.push(child);
+
+        ChildSpinner {
+            id,
+            state: self.state.clone(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.lock().unwrap().children.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn is_running(&self) -> bool {
+        self.running.load(Ordering::SeqCst)
+    }
+
+    /// Sets the frame rate of all children. Takes effect on the next frame
+    /// when the spinner is already running.
+    pub fn set_fps<V>(&mut self, fps: V) -> SpinnerResult<()>
+    where
+        V: Into<f64>,
+    {
+        *lock(&self.interval) = frames_per_second(fps.into())?;
+        Ok(())
+    }
+
+    pub fn set_color_policy<T>(&mut self, color_policy: T) -> SpinnerResult<()>
+    where
+        T: Into<ColorPolicy>,
+    {
+        self.color_policy = color_policy.into();
+        Ok(())
+    }
+
+    /// Same as on `Spinner`. In plain mode each child prints its message
+    /// once, again on every change, and its final line when it finishes.
+    /// Takes effect on the next `start`.
+    pub fn set_render_mode<T>(&mut self, render_mode: T) -> SpinnerResult<()>
+    where
+        T: Into<RenderMode>,
+    {
+        self.render_mode = render_mode.into();
+        Ok(())
+    }
+
+    pub fn set_output_stream<S>(&mut self, stream: S) -> SpinnerResult<()>
+    where
+        S: TryInto<SpinnerStream>,
+        S::Error: Into<SpinnerError>,
+    {
+        *self.output.lock().unwrap() = stream.try_into().map_err(Into::into)?;
+        Ok(())
+    }
+
+    /// Clears the child lines, runs `f` and redraws the children below
+    /// whatever `f` printed.
+    pub fn suspend<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        registry::suspend_with(&[self.active_lines()], f)
+    }
+
+    pub fn println<T>(&mut self, message: T) -> SpinnerResult<()>
+    where
+        T: AsRef<str>,
+    {
+        let output = self.output.clone();
+        self.suspend(|| {
+            let mut w = output.lock().unwrap();
+            writeln!(w, "{}", message.as_ref())?;
+            w.flush()
+        })
+        .map_err(SpinnerError::Io)
+    }
+
+    fn active_lines(&self) -> Arc<dyn ActiveSpinner> {
+        Arc::new(ActiveLines {
+            state: self.state.clone(),
+            output: self.output.clone(),
+            running: self.running.clone(),
+        })
+    }
+
+    pub fn start(&mut self) -> SpinnerResult<()> {
+        if self.is_running() {
+            return Err(SpinnerError::AlreadyRunning);
+        }
+        if let Some(id) = self.registration.take() {
+            ACTIVE_SPINNERS.unregister(id);
+        }
+        self.running.store(true, Ordering::SeqCst);
+        let is_terminal = self.output.lock().unwrap().is_terminal();
+        let mode = {
+            let mut state = self.state.lock().unwrap();
+            let mode = state
+                .config
+                .render_mode_or(self.render_mode)
+                .resolve(|| is_terminal);
+            state.interrupted = false;
+            state.mode = mode;
+            state.colors = self.color_policy.should_colorize(|| is_terminal);
+            mode
+        };
+
+        let state = self.state.clone();
+        let output = self.output.clone();
+        let running = self.running.clone();
+        let interval = self.interval.clone();
+
+        self.handle = Some(thread::spawn(move || {
+            if mode == RenderMode::Animated {
+                write!(output.lock().unwrap(), "\x1B[?25l")?; // hide cursor
+            }
+
+            while running.load(Ordering::SeqCst) {
+                draw(&state, &output)?;
+                state.lock().unwrap().tick();
+                let delay = *lock(&interval);
+                thread::sleep(delay);
+            }
+
+            draw(&state, &output)?;
+            let mut w = output.lock().unwrap();
+            if mode == RenderMode::Animated {
+                write!(w, "\x1B[?25h")?; // show cursor
+            }
+            w.flush().map_err(SpinnerError::Io)
+        }));
+        self.registration = Some(ACTIVE_SPINNERS.register(self.active_lines()));
+        Ok(())
+    }
+
+    pub fn stop(&mut self) -> SpinnerResult<()> {
+        if !self.is_running() {
+            return Err(SpinnerError::NotRunning);
+        }
+        self.running.store(false, Ordering::SeqCst);
+
+        let result = match self.handle.take().map(JoinHandle::join) {
+            Some(Ok(result)) => result,
+            Some(Err(_)) => Err(SpinnerError::ThreadPanicked),
+            None => Ok(()),
+        };
+
+        if let Some(id) = self.registration.take() {
+            ACTIVE_SPINNERS.unregister(id);
+        }
+        result
+    }
+}
+
+impl Default for MultiSpinner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Drop for MultiSpinner {
+    fn drop(&mut self) {
+        if self.is_running() {
+            let _ = self.stop();
+        } else if let Some(id) = self.registration.take() {
+            ACTIVE_SPINNERS.unregister(id);
+        }
+    }
+}
+
+#[derive(Clone)]
+pub struct ChildSpinner {
+    id: usize,
+    state: Arc<Mutex<MultiState>>,
+}
+
+impl ChildSpinner {
+    fn with_child<F>(&self, update: F) -> SpinnerResult<()>
+    where
+        F: FnOnce(&mut ChildLine) -> SpinnerResult<()>,
+    {
+        let mut state = self.state.lock().unwrap();
+        update(state.child_mut(self.id)?)
+    }
+
+    pub fn set_message<T>(&self, message: T) -> SpinnerResult<()>
+    where
+        T: Into<String>,
+    {
+        self.with_child(|child| {
+            let (text, dots) = trim_trailing_dots(message);
+            child.text = text;
+            child.dots = dots;
+            child.announced = false;
+            child.dot_count = 0;
+            Ok(())
+        })
+    }
+
+    pub fn set_style(&self, style: impl Into<StyleRef>) -> SpinnerResult<()> {
+        let style = style.into();
+        let data = style.resolve()?;
+        let config = self.state.lock().unwrap().config.clone();
+        let (_, data) = config.style_or(style, data);
+        self.with_child(|child| {
+            child.frames = data.frames;
+            child.frame_index = 0;
+            Ok(())
+        })
+    }
+
+    pub fn set_frames<S>(&self, frames: &[S]) -> SpinnerResult<()>
+    where
+        S: AsRef<str>,
+    {
+        let frames: Vec<String> = frames.iter().map(|s| s.as_ref().to_string()).collect();
+        validate_frames(&frames)?;
+        self.with_child(|child| {
+            child.frames = frames;
+            child.frame_index = 0;
+            child.dot_count = 0;
+            Ok(())
+        })
+    }
+
+    pub fn set_color_scheme<U>(
+        &self,
+        style_color: U,
+        message_color: U,
+        dots_color: U,
+    ) -> SpinnerResult<()>
+    where
+        U: Into<Option<Color>>,
+    {
+        self.with_child(|child| {
+            child.style_color = style_color.into();
+            child.text_color = message_color.into();
+            child.dot_color = dots_color.into();
+            Ok(())
+        })
+    }
+
+    pub fn is_finished(&self) -> bool {
+        let mut state = self.state.lock().unwrap();
+        state
+            .child_mut(self.id)
+            .map_or(true, |child| child.finish.is_some())
+    }
+
+    pub fn stop_with<S, T, U>(&self, symbol: S, message: T, color: U) -> SpinnerResult<()>
+    where
+        S: Into<String>,
+        T: Into<String>,
+        U: Into<Option<Color>>,
+    {
+        self.with_child(|child| {
+            if child.finish.is_some() {
+                return Err(SpinnerError::NotRunning);
+            }
+            child.finish = Some(FinishMessage {
+                symbol: symbol.into(),
+                text: message.into(),
+                color: color.into(),
+            });
+            Ok(())
+        })
+    }
+
+    pub fn succeed<T>(&self, message: T) -> SpinnerResult<()>
+    where
+        T: Into<String>,
+    {
+        let config = self.state.lock().unwrap().config.clone();
+        self.stop_with(config.symbols.success(), message, Color::Green)
+    }
+
+    pub fn fail<T>(&self, message: T) -> SpinnerResult<()>
+    where
+        T: Into<String>,
+    {
+        let config = self.state.lock().unwrap().config.clone();
+        self.stop_with(config.symbols.failure(), message, Color::Red)
+    }
+
+    pub fn warn<T>(&self, message: T) -> SpinnerResult<()>
+    where
+        T: Into<String>,
+    {
+        let config = self.state.lock().unwrap().config.clone();
+        self.stop_with(config.symbols.warning(), message, Color::Yellow)
+    }
+
+    pub fn info<T>(&self, message: T) -> SpinnerResult<()>
+    where
+        T: Into<String>,
+    {
+        let config = self.state.lock().unwrap().config.clone();
+        self.stop_with(config.symbols.info(), message, Color::Blue)
+    }
+
+    pub fn remove(&self) -> SpinnerResult<()> {
+        let mut state = self.state.lock().unwrap();
+        let index = state
+            .children
+            .iter()
+            .position(|child| child.id == self.id)
+            .ok_or(SpinnerError::Removed)?;
+        state.children.remove(index);
+        Ok(())
+    }
+}
+
+/// Strips every escape, including ones embedded in user messages, when
+/// colors are disabled.
+fn colorize(colors: bool, line: &str) -> Cow<'_, str> {
+    match colors {
+        true => Cow::Borrowed(line),
+        false => strip_ansi(line),
+    }
+}
+
+fn draw(state: &Mutex<MultiState>, output: &Mutex<SpinnerStream>) -> SpinnerResult<()> {
+    let mut state = state.lock().unwrap();
+    let frame = state.render();
+    let mut w = output.lock().unwrap();
+    write!(w, "{}", frame)?;
+    w.flush().map_err(SpinnerError::Io)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::CaptureWriter;
+    use crate::SUCCESS_SYMBOL;
+
+    fn plain(multi: &MultiSpinner) -> String {
+        let frame = multi.state.lock().unwrap().render();
+        let ansi = regex::Regex::new(r"\x1B\[[0-9;]*m").unwrap();
+        ansi.replace_all(&frame, "").to_string()
+    }
+
+    #[test]
+    fn test_add_children() {
+        let multi = MultiSpinner::new();
+        assert!(multi.is_empty());
+        multi.add("First");
+        multi.add("Second");
+        assert_eq!(multi.len(), 2);
+    }
+
+    #[test]
+    fn test_render_draws_one_line_per_child() {
+        let multi = MultiSpinner::new();
+        multi.add("First");
+        multi.add("Second");
+
+        let frame = plain(&multi);
+        assert_eq!(frame, "\r\x1B[K◐ First\n\r\x1B[K◐ Second\n\x1B[J");
+
+        let frame = plain(&multi);
+        assert!(frame.starts_with("\x1B[2A"));
+    }
+
+    #[test]
+    fn test_finished_child_keeps_final_line() {
+        let multi = MultiSpinner::new();
+        let child = multi.add("Compiling");
+        child.set_color_scheme(None, None, None).unwrap();
+        child.succeed("Compiled").unwrap();
+
+        assert!(child.is_finished());
+        assert!(plain(&multi).contains(&format!("{} Compiled\n", SUCCESS_SYMBOL)));
+        assert!(child.fail("Failed").is_err());
+    }
+
+    #[test]
+    fn test_remove_child() {
+        let multi = MultiSpinner::new();
+        let first = multi.add("First");
+        let second = multi.add("Second");
+        first.remove().unwrap();
+
+        assert_eq!(multi.len(), 1);
+        assert!(first.remove().is_err());
+        assert!(first.set_message("Gone").is_err());
+        assert!(second.set_message("Still here").is_ok());
+    }
+
+    #[test]
+    fn test_children_from_multiple_threads() {
+        let mut multi = MultiSpinner::new();
+        multi.start().unwrap();
+
+        let workers: Vec<_> = (0..8)
+            .map(|job| {
+                let child = multi.add(format!("Job {}", job));
+                thread::spawn(move || {
+                    child.set_message(format!("Job {} running", job)).unwrap();
+                    child.succeed(format!("Job {} done", job)).unwrap();
+                })
+            })
+            .collect();
+
+        for worker in workers {
+            worker.join().unwrap();
+        }
+
+        assert_eq!(multi.len(), 8);
+        assert!(multi.stop().is_ok());
+        assert!(!multi.is_running());
+    }
+
+    #[test]
+    fn test_interrupt_marks_unfinished_children() {
+        let multi = MultiSpinner::new();
+        let done = multi.add("Done");
+        let pending = multi.add("Pending");
+        done.succeed("Done").unwrap();
+
+        let frame = multi.state.lock().unwrap().interrupt(Some("Interrupted"));
+        assert!(frame.contains("Interrupted"));
+        assert!(pending.is_finished());
+        assert_eq!(plain(&multi), "");
+    }
+
+    #[test]
+    fn test_interrupt_without_message_clears_unfinished_children() {
+        let multi = MultiSpinner::new();
+        let done = multi.add("Done");
+        multi.add("Pending");
+        done.succeed("Done").unwrap();
+
+        multi.state.lock().unwrap().interrupt(None);
+        assert_eq!(multi.len(), 1);
+    }
+
+    #[test]
+    fn test_suspend_clears_and_pauses_rendering() {
+        let multi = MultiSpinner::new();
+        multi.add("First");
+        multi.add("Second");
+        multi.state.lock().unwrap().render();
+
+        let cleared = multi.state.lock().unwrap().suspend();
+        assert_eq!(cleared, "\x1B[2A\r\x1B[J");
+        assert_eq!(multi.state.lock().unwrap().render(), "");
+
+        multi.state.lock().unwrap().suspended -= 1;
+        let frame = plain(&multi);
+        assert!(!frame.starts_with("\x1B[2A"));
+        assert!(frame.contains("First") && frame.contains("Second"));
+    }
+
+    #[test]
+    fn test_println_while_running() {
+        let buffer = CaptureWriter::new();
+        let mut multi = MultiSpinner::new();
+        multi.set_output_stream(buffer.clone()).unwrap();
+        multi.set_render_mode(RenderMode::Animated).unwrap();
+        multi.add("Working");
+        multi.start().unwrap();
+        thread::sleep(Duration::from_millis(50));
+        multi.println("log line").unwrap();
+        assert_eq!(multi.suspend(|| 42), 42);
+        multi.stop().unwrap();
+
+        let output = buffer.contents();
+        let (_, after) = output.split_once("\x1B[1A\r\x1B[Jlog line\n").unwrap();
+        assert!(after.contains("Working"));
+    }
+
+    #[test]
+    fn test_set_empty_frames_is_rejected() {
+        let multi = MultiSpinner::new();
+        let child = multi.add("Working");
+        let empty: [&str; 0] = [];
+        assert!(matches!(
+            child.set_frames(&empty),
+            Err(SpinnerError::InvalidFrames)
+        ));
+        assert!(plain(&multi).contains("Working"));
+    }
+
+    #[test]
+    fn test_set_invalid_fps_is_rejected() {
+        let mut multi = MultiSpinner::new();
+        for fps in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+            assert!(matches!(
+                multi.set_fps(fps),
+                Err(SpinnerError::InvalidRate { .. })
+            ));
+        }
+        assert_eq!(*multi.interval.lock().unwrap(), DEFAULT_INTERVAL);
+    }
+
+    #[test]
+    fn test_set_fps_while_running() {
+        let mut multi = MultiSpinner::new();
+        multi.start().unwrap();
+        multi.set_fps(50).unwrap();
+        assert_eq!(*multi.interval.lock().unwrap(), Duration::from_millis(20));
+        multi.stop().unwrap();
+    }
+
+    #[test]
+    fn test_plain_mode_prints_changes_and_finals_once() {
+        let multi = MultiSpinner::new();
+        multi.state.lock().unwrap().mode = RenderMode::Plain;
+        let first = multi.add("Downloading...");
+        let second = multi.add("Compiling");
+
+        assert_eq!(plain(&multi), "Downloading...\nCompiling\n");
+        assert_eq!(plain(&multi), "");
+
+        first.set_message("Unpacking").unwrap();
+        second.succeed("Compiled").unwrap();
+        assert_eq!(
+            plain(&multi),
+            format!("Unpacking\n{} Compiled\n", SUCCESS_SYMBOL)
+        );
+        assert_eq!(plain(&multi), "");
+    }
+
+    #[test]
+    fn test_hidden_mode_prints_nothing() {
+        let multi = MultiSpinner::new();
+        multi.state.lock().unwrap().mode = RenderMode::Hidden;
+        multi.add("Working").succeed("Done").unwrap();
+        assert_eq!(plain(&multi), "");
+    }
+
+    #[test]
+    fn test_with_config() {
+        let buffer = CaptureWriter::new();
+        let mut multi = MultiSpinner::with_config(SpinnerConfig {
+            render_mode: Some(RenderMode::Hidden),
+            symbols: crate::Symbols {
+                success: Some("ok".to_string()),
+                ..Default::default()
+            },
+            ..SpinnerConfig::default()
+        });
+        multi.set_output_stream(buffer.clone()).unwrap();
+        multi.set_render_mode(RenderMode::Animated).unwrap();
+        let child = multi.add("Working");
+        multi.start().unwrap();
+        child.succeed("Done").unwrap();
+        multi.stop().unwrap();
+
+        assert_eq!(buffer.contents(), "");
+        let state = multi.state.lock().unwrap();
+        assert_eq!(state.children[0].finish.as_ref().unwrap().symbol, "ok");
+    }
+
+    #[test]
+    fn test_auto_mode_is_plain_off_terminal() {
+        let buffer = CaptureWriter::new();
+        let mut multi = MultiSpinner::new();
+        multi.set_output_stream(buffer.clone()).unwrap();
+        multi.set_color_policy(ColorPolicy::Never).unwrap();
+        let child = multi.add("Working");
+        multi.start().unwrap();
+        thread::sleep(Duration::from_millis(50));
+        child.succeed("Done").unwrap();
+        multi.stop().unwrap();
+
+        let output = buffer.contents();
+        assert_eq!(output, format!("Working\n{} Done\n", SUCCESS_SYMBOL));
+    }
+
+    #[test]
+    fn test_strips_escapes_from_messages_without_colors() {
+        let multi = MultiSpinner::new();
+        let child = multi.add("\x1B]8;;https://example.com\x07Link\x1B]8;;\x07");
+        let frame = multi.state.lock().unwrap().render();
+        assert_eq!(frame, "\r\x1B[K◐ Link\n\x1B[J");
+
+        multi.state.lock().unwrap().mode = RenderMode::Plain;
+        child.succeed("\x1B[1mDone\x1B[0m").unwrap();
+        let frame = multi.state.lock().unwrap().render();
+        assert_eq!(frame, format!("{} Done\n", SUCCESS_SYMBOL));
+    }
+
+    #[test]
+    fn test_set_render_mode() {
+        let mut multi = MultiSpinner::new();
+        assert!(multi.set_render_mode("hidden").is_ok());
+        assert_eq!(multi.render_mode, RenderMode::Hidden);
+    }
+
+    #[test]
+    fn test_stop_stopped_multi_spinner() {
+        let mut multi = MultiSpinner::new();
+        assert!(multi.stop().is_err());
+    }
+}
@@ -0,0 +1,388 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use crate::clock::{Clock, SystemClock};
+
+const BAR_WIDTH: usize = 20;
+const BAR_FILLED: &str = "█";
+const BAR_EMPTY: &str = "░";
+
+/// Shortest interval the rate is sampled over, so that bursts of tiny
+/// increments do not produce wild instantaneous rates.
+const RATE_WINDOW: Duration = Duration::from_millis(100);
+/// Time constant of the exponential smoothing: older samples lose about two
+/// thirds of their weight every `RATE_SMOOTHING`.
+const RATE_SMOOTHING: Duration = Duration::from_secs(3);
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ProgressUnit {
+    #[default]
+    Count,
+    Bytes,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProgressUpdate {
+    Total(u64),
+    Inc(u64),
+    Position(u64),
+    Unit(ProgressUnit),
+}
+
+#[derive(Clone)]
+pub struct Progress {
+    clock: Arc<dyn Clock>,
+    position: u64,
+    total: Option<u64>,
+    unit: ProgressUnit,
+    last_sample: Option<(Instant, u64)>,
+    smoothed_rate: Option<f64>,
+}
+
+impl Progress {
+    pub fn new() -> Self {
+        Self {
+            clock: Arc::new(SystemClock),
+            position: 0,
+            total: None,
+            unit: ProgressUnit::default(),
+            last_sample: None,
+            smoothed_rate: None,
+        }
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    pub fn apply(&mut self, update: ProgressUpdate) {
+        self.apply_at(update, self.clock.now());
+    }
+
+    fn apply_at(&mut self, update: ProgressUpdate, now: Instant) {
+        if !matches!(update, ProgressUpdate::Unit(_)) && self.last_sample.is_none() {
+            self.last_sample = Some((now, self.position));
+        }
+        match update {
+            ProgressUpdate::Total(total) => self.total = Some(total),
+            ProgressUpdate::Inc(delta) => self.position = self.position.saturating_add(delta),
+            ProgressUpdate::Position(position) => self.position = position,
+            ProgressUpdate::Unit(unit) => {
+                self.unit = unit;
+                return;
+            }
+        }
+        self.sample(now);
+    }
+
+    fn sample(&mut self, now: Instant) {
+        let Some((sampled_at, sampled_position)) = self.last_sample else {
+            return;
+        };
+        let elapsed = now.saturating_duration_since(sampled_at);
+        if elapsed < RATE_WINDOW {
+            return;
+        }
+        let rate = self.position.saturating_sub(sampled_position) as f64 / elapsed.as_secs_f64();
+        self.smoothed_rate = Some(match self.smoothed_rate {
+            Some(smoothed) => smooth(smoothed, rate, elapsed),
+            None => rate,
+        });
+        self.last_sample = Some((now, self.position));
+    }
+
+    pub fn percent(&self) -> Option<u64> {
+        self.fraction()
+            .map(|fraction| (fraction * 100.0).floor() as u64)
+    }
+
+    pub fn fraction(&self) -> Option<f64> {
+        self.total.map(|total| match total {
+            0 => 1.0,
+            total => (self.position as f64 / total as f64).min(1.0),
+        })
+    }
+
+    /// Units per second, exponentially smoothed. `None` until the first
+    /// sampling window has passed.
+    pub fn rate(&self) -> Option<f64> {
+        self.rate_at(self.clock.now())
+    }
+
+    fn rate_at(&self, now: Instant) -> Option<f64> {
+        let (sampled_at, sampled_position) = self.last_sample?;
+        // Fold in the time since the last sample, so that the rate decays
+        // while progress stalls.
+        let pending = now.saturating_duration_since(sampled_at);
+        if pending < RATE_WINDOW {
+            return self.smoothed_rate;
+        }
+        let rate = self.position.saturating_sub(sampled_position) as f64 / pending.as_secs_f64();
+        Some(match self.smoothed_rate {
+            Some(smoothed) => smooth(smoothed, rate, pending),
+            None => rate,
+        })
+    }
+
+    pub fn eta(&self) -> Option<Duration> {
+        self.eta_at(self.clock.now())
+    }
+
+    fn eta_at(&self, now: Instant) -> Option<Duration> {
+        let total = self.total?;
+        let rate = self.rate_at(now)?;
+        if rate <= 0.0 {
+            return None;
+        }
+        let remaining = total.saturating_sub(self.position) as f64;
+        Duration::try_from_secs_f64(remaining / rate).ok()
+    }
+
+    pub fn format_position(&self) -> String {
+        self.format_amount(self.position)
+    }
+
+    pub fn format_total(&self) -> Option<String> {
+        self.total.map(|total| self.format_amount(total))
+    }
+
+    pub fn format_rate(&self) -> Option<String> {
+        let rate = self.rate()?;
+        Some(match self.unit {
+            ProgressUnit::Count => format!("{:.1}/s", rate),
+            ProgressUnit::Bytes => format!("{}/s", format_bytes(rate as u64)),
+        })
+    }
+
+    fn format_amount(&self, amount: u64) -> String {
+        match self.unit {
+            ProgressUnit::Count => amount.to_string(),
+            ProgressUnit::Bytes => format_bytes(amount),
+        }
+    }
+
+    pub fn bar(&self) -> Option<String> {
+        let fraction = self.fraction()?;
+        let filled = (fraction * BAR_WIDTH as f64).round() as usize;
+        Some(format!(
+            "{}{}",
+            BAR_FILLED.repeat(filled),
+            BAR_EMPTY.repeat(BAR_WIDTH - filled)
+        ))
+    }
+
+    pub fn render(&self) -> Option<String> {
+        let rate = match self.unit {
+            ProgressUnit::Count => String::new(),
+            ProgressUnit::Bytes => {
+                format!(" {}", self.format_rate().unwrap_or_else(|| "-".to_string()))
+            }
+        };
+        let total = match self.format_total() {
+            Some(total) => total,
+            // Transferred bytes are worth showing even without a total.
+            None if self.unit == ProgressUnit::Bytes => {
+                return Some(format!("{}{}", self.format_position(), rate));
+            }
+            None => return None,
+        };
+        let percent = self.percent()?;
+        let eta = match self.eta() {
+            Some(eta) => format_duration(eta),
+            None => "-".to_string(),
+        };
+
+        Some(format!(
+            "{} {:>3}% {}/{}{} ETA {}",
+            self.bar()?,
+            percent,
+            self.format_position(),
+            total,
+            rate,
+            eta
+        ))
+    }
+}
+
+impl Default for Progress {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn smooth(smoothed: f64, rate: f64, elapsed: Duration) -> f64 {
+    let weight = 1.0 - (-elapsed.as_secs_f64() / RATE_SMOOTHING.as_secs_f64()).exp();
+    smoothed + weight * (rate - smoothed)
+}
+
+pub fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 6] = ["KiB", "MiB", "GiB", "TiB", "PiB", "EiB"];
+    if bytes < 1024 {
+        return format!("{} B", bytes);
+    }
+    let mut value = bytes as f64 / 1024.0;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    format!("{:.1} {}", value, UNITS[unit])
+}
+
+pub fn format_duration(duration: Duration) -> String {
+    let seconds = duration.as_secs();
+    match seconds {
+        0..=59 => format!("{}s", seconds),
+        60..=3599 => format!("{}m {:02}s", seconds / 60, seconds % 60),
+        _ => format!("{}h {:02}m", seconds / 3600, (seconds % 3600) / 60),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::testing::ManualClock;
+
+    #[test]
+    fn test_progress_starts_indeterminate() {
+        let progress = Progress::new();
+        assert_eq!(progress.total, None);
+        assert_eq!(progress.render(), None);
+    }
+
+    #[test]
+    fn test_progress_apply_updates() {
+        let mut progress = Progress::new();
+        progress.apply(ProgressUpdate::Inc(5));
+        progress.apply(ProgressUpdate::Inc(5));
+        assert_eq!(progress.position, 10);
+
+        progress.apply(ProgressUpdate::Position(3));
+        assert_eq!(progress.position, 3);
+
+        progress.apply(ProgressUpdate::Total(12));
+        assert_eq!(progress.total, Some(12));
+        assert_eq!(progress.fraction(), Some(0.25));
+    }
+
+    #[test]
+    fn test_progress_render() {
+        let mut progress = Progress::new();
+        progress.apply(ProgressUpdate::Total(200));
+        progress.apply(ProgressUpdate::Position(50));
+
+        let rendered = progress.render().unwrap();
+        assert!(rendered.starts_with(&format!("{}{}", BAR_FILLED.repeat(5), BAR_EMPTY.repeat(15))));
+        assert!(rendered.contains(" 25% 50/200 ETA "));
+    }
+
+    #[test]
+    fn test_progress_fraction_is_clamped() {
+        let mut progress = Progress::new();
+        progress.apply(ProgressUpdate::Total(10));
+        progress.apply(ProgressUpdate::Position(20));
+        assert_eq!(progress.fraction(), Some(1.0));
+        assert_eq!(progress.bar().unwrap(), BAR_FILLED.repeat(BAR_WIDTH));
+    }
+
+    #[test]
+    fn test_progress_zero_total_is_complete() {
+        let mut progress = Progress::new();
+        progress.apply(ProgressUpdate::Total(0));
+        assert_eq!(progress.fraction(), Some(1.0));
+    }
+
+    #[test]
+    fn test_progress_render_bytes() {
+        let clock = ManualClock::new();
+        let mut progress = Progress::new();
+        progress.set_clock(Arc::new(clock.clone()));
+        progress.apply(ProgressUpdate::Unit(ProgressUnit::Bytes));
+        progress.apply(ProgressUpdate::Position(1536));
+        assert_eq!(progress.render().unwrap(), "1.5 KiB -");
+
+        clock.advance(RATE_WINDOW);
+        let rendered = progress.render().unwrap();
+        assert!(rendered.starts_with("1.5 KiB "));
+        assert!(rendered.ends_with("/s"));
+
+        progress.apply(ProgressUpdate::Total(3 * 1024 * 1024));
+        let rendered = progress.render().unwrap();
+        assert!(rendered.contains("  0% 1.5 KiB/3.0 MiB "));
+        assert!(rendered.contains("/s ETA "));
+    }
+
+    #[test]
+    fn test_progress_unit_does_not_start_timer() {
+        let mut progress = Progress::new();
+        progress.apply(ProgressUpdate::Unit(ProgressUnit::Bytes));
+        assert!(progress.last_sample.is_none());
+        assert_eq!(progress.rate(), None);
+    }
+
+    #[test]
+    fn test_rate_is_none_before_first_window() {
+        let start = Instant::now();
+        let mut progress = Progress::new();
+        progress.apply_at(ProgressUpdate::Inc(5), start);
+        assert_eq!(progress.rate_at(start), None);
+        assert_eq!(progress.rate_at(start + Duration::from_millis(50)), None);
+        assert_eq!(progress.rate_at(start + RATE_WINDOW), Some(50.0));
+    }
+
+    #[test]
+    fn test_rate_follows_steady_progress() {
+        let start = Instant::now();
+        let mut progress = Progress::new();
+        progress.apply_at(ProgressUpdate::Total(1000), start);
+        for second in 1..=10 {
+            progress.apply_at(ProgressUpdate::Inc(50), start + Duration::from_secs(second));
+        }
+        let now = start + Duration::from_secs(10);
+        assert!((progress.rate_at(now).unwrap() - 50.0).abs() < 1e-9);
+        assert_eq!(progress.eta_at(now), Some(Duration::from_secs(10)));
+    }
+
+    #[test]
+    fn test_rate_smooths_bursts() {
+        let start = Instant::now();
+        let mut progress = Progress::new();
+        progress.apply_at(ProgressUpdate::Total(10_000), start);
+        for second in 1..=10 {
+            progress.apply_at(ProgressUpdate::Inc(10), start + Duration::from_secs(second));
+        }
+        progress.apply_at(ProgressUpdate::Inc(1000), start + Duration::from_secs(11));
+
+        let rate = progress.rate_at(start + Duration::from_secs(11)).unwrap();
+        assert!(rate > 10.0 && rate < 1000.0 / 2.0);
+    }
+
+    #[test]
+    fn test_rate_decays_while_stalled() {
+        let start = Instant::now();
+        let mut progress = Progress::new();
+        progress.apply_at(ProgressUpdate::Total(1000), start);
+        progress.apply_at(ProgressUpdate::Inc(100), start + Duration::from_secs(1));
+
+        let steady = progress.rate_at(start + Duration::from_secs(1)).unwrap();
+        let stalled = progress.rate_at(start + Duration::from_secs(10)).unwrap();
+        assert!(stalled < steady / 10.0);
+        assert!(progress.eta_at(start + Duration::from_secs(10)).unwrap() > Duration::from_secs(9));
+    }
+
+    #[test]
+    fn test_format_bytes() {
+        assert_eq!(format_bytes(0), "0 B");
+        assert_eq!(format_bytes(1023), "1023 B");
+        assert_eq!(format_bytes(1024), "1.0 KiB");
+        assert_eq!(format_bytes(12_897_485), "12.3 MiB");
+        assert_eq!(format_bytes(5 * 1024 * 1024 * 1024), "5.0 GiB");
+    }
+
+    #[test]
+    fn test_format_duration() {
+        assert_eq!(format_duration(Duration::from_secs(42)), "42s");
+        assert_eq!(format_duration(Duration::from_secs(65)), "1m 05s");
+        assert_eq!(format_duration(Duration::from_secs(7260)), "2h 01m");
+    }
+}
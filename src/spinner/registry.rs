@@ -0,0 +1,421 @@
+use std::cell::Cell;
+use std::io::Write;
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Condvar, Mutex, MutexGuard,
+};
+use std::time::Duration;
+
+use colored::Color;
+use crossbeam::channel::{bounded, RecvTimeoutError};
+use lazy_static::lazy_static;
+
+use super::{
+    color::{paint, strip_ansi},
+    message::SuspendMessage,
+    render_mode::RenderMode,
+    state::SpinnerState,
+};
+
+/// Something currently drawing to the terminal that can be torn down from
+/// outside its render thread, e.g. from a panic hook or a signal handler.
+pub trait ActiveSpinner: Send + Sync {
+    fn interrupt(&self, message: Option<&str>);
+
+    /// Clears what is drawn and keeps it from being redrawn until the
+    /// returned guard is dropped.
+    fn suspend(&self) -> Option<SuspendGuard>;
+
+    /// Shows a span name in place of the message, or restores the message
+    /// shown before the first span with `None`. Only single-line spinners
+    /// support this; a `MultiSpinner` keeps its children's messages.
+    #[cfg(feature = "tracing")]
+    fn show_span(&self, _name: Option<&str>) {}
+}
+
+pub struct SuspendGuard {
+    _release: Box<dyn Send>,
+}
+
+impl SuspendGuard {
+    pub fn new<T>(release: T) -> Self
+    where
+        T: Send + 'static,
+    {
+        Self {
+            _release: Box::new(release),
+        }
+    }
+}
+
+thread_local! {
+    static SUSPENDED: Cell<bool> = const { Cell::new(false) };
+    /// The spinner whose render loop runs on this thread, if any. Listeners
+    /// run on that thread, and a listener suspending its own spinner must not
+    /// wait for the render loop it is blocking.
+    static RENDERING: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+/// Runs `f`, a render loop or one step of it, marked as rendering the
+/// spinner with the given state id.
+pub fn rendering<F, R>(id: usize, f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    struct Restore(Option<usize>);
+
+    impl Drop for Restore {
+        fn drop(&mut self) {
+            RENDERING.with(|rendering| rendering.set(self.0));
+        }
+    }
+
+    let _restore = Restore(RENDERING.with(|rendering| rendering.replace(Some(id))));
+    f()
+}
+
+/// Whether the current thread is running the render loop of the spinner
+/// with the given state id.
+pub fn is_rendering(id: usize) -> bool {
+    RENDERING.with(|rendering| rendering.get() == Some(id))
+}
+
+struct Suspended {
+    _guards: Vec<SuspendGuard>,
+}
+
+impl Drop for Suspended {
+    fn drop(&mut self) {
+        SUSPENDED.with(|suspended| suspended.set(false));
+    }
+}
+
+/// Runs `f` while `spinners` are suspended. A nested call on the same thread
+/// runs `f` directly, since waiting for a render thread that is already
+/// blocked on this thread would never return.
+pub fn suspend_with<F, R>(spinners: &[Arc<dyn ActiveSpinner>], f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    if SUSPENDED.with(Cell::get) {
+        return f();
+    }
+    let guards = spinners
+        .iter()
+        .filter_map(|spinner| spinner.suspend())
+        .collect();
+    SUSPENDED.with(|suspended| suspended.set(true));
+    let _suspended = Suspended { _guards: guards };
+    f()
+}
+
+pub struct Registry {
+    next_id: AtomicUsize,
+    active: Mutex<Vec<(usize, Arc<dyn ActiveSpinner>)>>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self {
+            next_id: AtomicUsize::new(0),
+            active: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn register(&self, spinner: Arc<dyn ActiveSpinner>) -> usize {
+        let id = self.next_id.fetch_add(1, Ordering::SeqCst);
+        lock(&self.active).push((id, spinner));
+        id
+    }
+
+    pub fn unregister(&self, id: usize) {
+        lock(&self.active).retain(|(active_id, _)| *active_id != id);
+    }
+
+    pub fn interrupt_all(&self, message: Option<&str>) {
+        for spinner in self.snapshot() {
+            spinner.interrupt(message);
+        }
+    }
+
+    pub fn suspend_all<F, R>(&self, f: F) -> R
+    where
+        F: FnOnce() -> R,
+    {
+        suspend_with(&self.snapshot(), f)
+    }
+
+    #[cfg(feature = "tracing")]
+    pub fn show_span_all(&self, name: Option<&str>) {
+        for spinner in self.snapshot() {
+            spinner.show_span(name);
+        }
+    }
+
+    /// Snapshots the entries so spinners are never called under the registry
+    /// lock.
+    fn snapshot(&self) -> Vec<Arc<dyn ActiveSpinner>> {
+        lock(&self.active)
+            .iter()
+            .map(|(_, spinner)| spinner.clone())
+            .collect()
+    }
+}
+
+lazy_static! {
+    pub static ref ACTIVE_SPINNERS: Registry = Registry::new();
+}
+
+/// Clears every active spinner, runs `f` and redraws the spinners afterwards,
+/// so that output from other code, such as a logger, stays above them.
+pub fn suspend<F, R>(f: F) -> R
+where
+    F: FnOnce() -> R,
+{
+    ACTIVE_SPINNERS.suspend_all(f)
+}
+
+/// The single line drawn by a `Spinner`.
+pub struct ActiveLine {
+    pub state: SpinnerState,
+    pub running: Arc<AtomicBool>,
+    pub paused: Arc<(Mutex<bool>, Condvar)>,
+    pub rendering: Arc<AtomicBool>,
+}
+
+impl ActiveSpinner for ActiveLine {
+    fn interrupt(&self, message: Option<&str>) {
+        interrupt_line(&self.state, &self.running, message);
+        self.state.interrupted(message);
+    }
+
+    fn suspend(&self) -> Option<SuspendGuard> {
+        if !self.running.load(Ordering::SeqCst) {
+            return None;
+        }
+        if is_rendering(self.state.id()) {
+            // Called from a listener on the render thread, which draws
+            // nothing until the listener returns.
+            let _ = self.state.clear_line();
+            return None;
+        }
+        let paused = *lock(&self.paused.0);
+        if paused {
+            // A paused render thread does not read messages, so the frozen
+            // frame is cleared here; it is redrawn on resume.
+            let output = self.state.output();
+            let mut w = lock(&output);
+            if w.is_terminal() {
+                let _ = write!(w, "\r\x1B[K");
+                let _ = w.flush();
+            }
+            return None;
+        }
+
+        let (acquired, acquired_rx) = bounded(0);
+        let (release, released) = bounded::<()>(0);
+        self.state
+            .request_suspend(SuspendMessage { acquired, released })
+            .ok()?;
+        loop {
+            match acquired_rx.recv_timeout(Duration::from_millis(10)) {
+                Ok(()) => return Some(SuspendGuard::new(release)),
+                // The render thread may exit without reading the message,
+                // e.g. when it is stopped or fails to write.
+                Err(RecvTimeoutError::Timeout) if self.rendering.load(Ordering::SeqCst) => {}
+                Err(_) => return None,
+            }
+        }
+    }
+
+    #[cfg(feature = "tracing")]
+    fn show_span(&self, name: Option<&str>) {
+        let _ = self.state.update(super::message::UpdateMessage::SpanName(
+            name.map(str::to_owned),
+        ));
+    }
+}
+
+/// Stops a single-line render loop and clears its line, leaving the optional
+/// interrupted line in its place. Escapes are only written if the render
+/// loop has an animated line drawn, so piped output stays plain.
+pub fn interrupt_line(state: &SpinnerState, running: &AtomicBool, message: Option<&str>) {
+    running.store(false, Ordering::SeqCst);
+
+    let drawn = state.take_line();
+    let output = state.output();
+    let mut w = lock(&output);
+    if drawn {
+        let _ = write!(w, "\r\x1B[K");
+    }
+    let hidden = state.render_mode().resolve(|| w.is_terminal()) == RenderMode::Hidden;
+    if let Some(message) = message.filter(|_| !hidden) {
+        let config = state.config();
+        let symbol = config.symbols.failure();
+        let line = match state.color_policy().should_colorize(|| w.is_terminal()) {
+            true => format!("{} {}", paint(symbol, Some(Color::Red)), message),
+            false => strip_ansi(&format!("{} {}", symbol, message)).into_owned(),
+        };
+        let _ = writeln!(w, "{}", line);
+    }
+    if drawn {
+        let _ = write!(w, "\x1B[?25h"); // show cursor
+    }
+    let _ = w.flush();
+}
+
+/// Locks a mutex even if a panicking thread poisoned it, since restoring the
+/// terminal matters most exactly when something has already gone wrong.
+pub fn lock<T: ?Sized>(mutex: &Mutex<T>) -> MutexGuard<'_, T> {
+    mutex
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::spinner::{color::ColorPolicy, message::UpdateMessage, stream::SpinnerStream};
+    use crate::testing::CaptureWriter;
+
+    struct Recorder(Mutex<Vec<Option<String>>>);
+
+    impl ActiveSpinner for Recorder {
+        fn interrupt(&self, message: Option<&str>) {
+            self.0.lock().unwrap().push(message.map(str::to_string));
+        }
+
+        fn suspend(&self) -> Option<SuspendGuard> {
+            self.0.lock().unwrap().push(Some("suspend".to_string()));
+            Some(SuspendGuard::new(()))
+        }
+
+        #[cfg(feature = "tracing")]
+        fn show_span(&self, name: Option<&str>) {
+            self.0.lock().unwrap().push(name.map(str::to_string));
+        }
+    }
+
+    #[test]
+    fn test_register_and_unregister() {
+        let registry = Registry::new();
+        let recorder = Arc::new(Recorder(Mutex::new(Vec::new())));
+        let first = registry.register(recorder.clone());
+        let second = registry.register(recorder);
+        assert_ne!(first, second);
+        assert_eq!(registry.active.lock().unwrap().len(), 2);
+
+        registry.unregister(first);
+        assert_eq!(registry.active.lock().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_interrupt_all() {
+        let registry = Registry::new();
+        let recorder = Arc::new(Recorder(Mutex::new(Vec::new())));
+        let id = registry.register(recorder.clone());
+        registry.interrupt_all(Some("Interrupted"));
+        registry.unregister(id);
+        registry.interrupt_all(None);
+
+        let calls = recorder.0.lock().unwrap();
+        assert_eq!(*calls, vec![Some("Interrupted".to_string())]);
+    }
+
+    #[test]
+    fn test_suspend_all_is_reentrant() {
+        let registry = Registry::new();
+        let recorder = Arc::new(Recorder(Mutex::new(Vec::new())));
+        registry.register(recorder.clone());
+
+        let value = registry.suspend_all(|| registry.suspend_all(|| 42));
+        assert_eq!(value, 42);
+        registry.suspend_all(|| ());
+
+        let calls = recorder.0.lock().unwrap();
+        assert_eq!(*calls, vec![Some("suspend".to_string()); 2]);
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_show_span_all() {
+        let registry = Registry::new();
+        let recorder = Arc::new(Recorder(Mutex::new(Vec::new())));
+        registry.register(recorder.clone());
+        registry.show_span_all(Some("Fetching"));
+        registry.show_span_all(None);
+
+        let calls = recorder.0.lock().unwrap();
+        assert_eq!(*calls, vec![Some("Fetching".to_string()), None]);
+    }
+
+    fn active_line(running: bool) -> ActiveLine {
+        ActiveLine {
+            state: SpinnerState::new("Loading"),
+            running: Arc::new(AtomicBool::new(running)),
+            paused: Arc::new((Mutex::new(false), Condvar::new())),
+            rendering: Arc::new(AtomicBool::new(false)),
+        }
+    }
+
+    #[test]
+    fn test_interrupt_active_line_stops_rendering() {
+        let line = active_line(true);
+        line.interrupt(None);
+        assert!(!line.running.load(Ordering::SeqCst));
+    }
+
+    fn captured_state(buffer: &CaptureWriter) -> SpinnerState {
+        let mut state = SpinnerState::new("Loading");
+        state
+            .apply(UpdateMessage::Stream(SpinnerStream::from(buffer.clone())))
+            .unwrap();
+        state
+    }
+
+    #[test]
+    fn test_interrupt_line_follows_color_policy() {
+        let buffer = CaptureWriter::new();
+        let mut state = captured_state(&buffer);
+        let running = AtomicBool::new(true);
+        state
+            .apply(UpdateMessage::ColorPolicy(ColorPolicy::Always))
+            .unwrap();
+        interrupt_line(&state, &running, Some("Stopped"));
+        assert!(buffer.contents().contains("\x1B[31m"));
+
+        buffer.clear();
+        state
+            .apply(UpdateMessage::ColorPolicy(ColorPolicy::Never))
+            .unwrap();
+        interrupt_line(&state, &running, Some("\x1B[1mStopped"));
+        let symbol = state.config().symbols.failure().to_string();
+        assert_eq!(buffer.contents(), format!("{} Stopped\n", symbol));
+    }
+
+    #[test]
+    fn test_interrupt_line_clears_only_a_drawn_line() {
+        let buffer = CaptureWriter::new();
+        let mut state = captured_state(&buffer);
+        state
+            .apply(UpdateMessage::RenderMode(RenderMode::Animated))
+            .unwrap();
+        assert!(state.tick().is_none());
+        buffer.clear();
+
+        let running = AtomicBool::new(true);
+        interrupt_line(&state, &running, None);
+        assert_eq!(buffer.contents(), "\r\x1B[K\x1B[?25h");
+        assert!(!state.take_line());
+
+        buffer.clear();
+        interrupt_line(&state, &running, None);
+        assert_eq!(buffer.contents(), "");
+    }
+
+    #[test]
+    fn test_suspend_active_line_without_render_thread() {
+        assert!(active_line(false).suspend().is_none());
+        assert!(active_line(true).suspend().is_none());
+    }
+}
@@ -0,0 +1,76 @@
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum RenderMode {
+    /// Animated when the output stream is a terminal, plain otherwise.
+    #[default]
+    Auto,
+    /// Redraws the spinner line in place using ANSI escapes.
+    Animated,
+    /// Prints the message on start and on changes plus the final state, one
+    /// line each, without escapes or animation.
+    Plain,
+    /// Prints nothing at all.
+    Hidden,
+}
+
+impl RenderMode {
+    pub fn resolve<F>(self, is_terminal: F) -> RenderMode
+    where
+        F: FnOnce() -> bool,
+    {
+        match self {
+            RenderMode::Auto if is_terminal() => RenderMode::Animated,
+            RenderMode::Auto => RenderMode::Plain,
+            mode => mode,
+        }
+    }
+}
+
+impl From<&str> for RenderMode {
+    fn from(value: &str) -> Self {
+        match value.to_lowercase().as_str() {
+            "animated" => Self::Animated,
+            "plain" => Self::Plain,
+            "hidden" => Self::Hidden,
+            _ => Self::Auto,
+        }
+    }
+}
+
+impl std::fmt::Display for RenderMode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Auto => write!(f, "auto"),
+            Self::Animated => write!(f, "animated"),
+            Self::Plain => write!(f, "plain"),
+            Self::Hidden => write!(f, "hidden"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_resolve_auto_on_terminal() {
+        assert_eq!(RenderMode::Auto.resolve(|| true), RenderMode::Animated);
+    }
+
+    #[test]
+    fn test_resolve_auto_off_terminal() {
+        assert_eq!(RenderMode::Auto.resolve(|| false), RenderMode::Plain);
+    }
+
+    #[test]
+    fn test_resolve_explicit_mode_ignores_terminal() {
+        assert_eq!(RenderMode::Animated.resolve(|| false), RenderMode::Animated);
+        assert_eq!(RenderMode::Hidden.resolve(|| true), RenderMode::Hidden);
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!(RenderMode::from("PLAIN"), RenderMode::Plain);
+        assert_eq!(RenderMode::from("hidden"), RenderMode::Hidden);
+        assert_eq!(RenderMode::from("invalid"), RenderMode::Auto);
+    }
+}
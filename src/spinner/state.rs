@@ -1,24 +1,44 @@
+use std::borrow::Cow;
 use std::io::Write;
-use std::sync::atomic::Ordering;
-use std::sync::{atomic::AtomicBool, Arc, Condvar, Mutex};
-use std::thread;
-use std::time::Duration;
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Condvar, Mutex};
+use std::time::{Duration, Instant};
 
-use colored::*;
+use colored::Color;
 use unicode_width::UnicodeWidthStr;
 
 use super::alignment::Alignment;
 use super::builtins::{get_spinner_data, SpinnerStyle};
-use super::{channel::Channel, message::UpdateMessage};
-use crate::{spinner::message::SpinnerMessage, SpinnerError, SpinnerResult, SpinnerStream};
+use super::color::{paint, strip_ansi, ColorPolicy};
+use super::event::{Outcome, SpinnerEvent};
+use super::progress::{format_duration, Progress};
+use super::render_mode::RenderMode;
+use super::template::{Key, Template};
+use super::{
+    channel::Channel,
+    message::{FinishMessage, SuspendMessage, UpdateMessage},
+};
+use crate::{
+    clock::{Clock, SystemClock},
+    event_emitter::{self, EventEmitter},
+    spinner::message::SpinnerMessage,
+    SpinnerConfig, SpinnerError, SpinnerResult, SpinnerStream,
+};
+
+static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
 
 #[derive(Clone)]
 pub struct SpinnerState {
+    id: usize,
     channel: Channel<SpinnerMessage>,
+    emitter: EventEmitter,
+    clock: Arc<dyn Clock>,
+    /// The defaults the spinner was built from, which also decide the render
+    /// mode, style and symbols it uses later on.
+    config: Arc<SpinnerConfig>,
     output: Arc<Mutex<SpinnerStream>>,
     dots: String,
     text: String,
-    spinner_style: SpinnerStyle,
     frames: Vec<String>,
     frame_duration: u64,
     reverse: Arc<AtomicBool>,
@@ -27,6 +47,24 @@ pub struct SpinnerState {
     text_color: Option<Color>,
     dot_color: Option<Color>,
     interval: Option<Duration>,
+    progress: Progress,
+    template: Option<Template>,
+    prefix: String,
+    started: Option<Instant>,
+    paused_since: Option<Instant>,
+    paused_for: Duration,
+    show_elapsed: bool,
+    render_mode: RenderMode,
+    color_policy: ColorPolicy,
+    /// Shared by every copy, so that any of them can tell whether the render
+    /// thread has a line drawn.
+    cursor_hidden: Arc<AtomicBool>,
+    announced: bool,
+    frame_index: usize,
+    dot_count: usize,
+    frame_events: bool,
+    #[cfg(feature = "tracing")]
+    message_before_spans: Option<String>,
 }
 
 impl SpinnerState {
@@ -38,8 +76,7 @@ impl SpinnerState {
 
         let (text, dots) = trim_trailing_dots(message);
 
-        let spinner_style = SpinnerStyle::default();
-        let data = get_spinner_data(&spinner_style);
+        let data = get_spinner_data(&SpinnerStyle::default());
         let frames = data.frames;
         let frame_duration = data.frame_duration;
 
@@ -52,12 +89,24 @@ impl SpinnerState {
 
         let interval = None;
 
+        let progress = Progress::new();
+
+        let template = None;
+        let prefix = String::new();
+        let started = None;
+
+        let render_mode = RenderMode::default();
+        let color_policy = ColorPolicy::default();
+
         Self {
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
             channel,
+            emitter: EventEmitter::new(),
+            clock: Arc::new(SystemClock),
+            config: SpinnerConfig::shared(),
             output,
             dots,
             text,
-            spinner_style,
             frames,
             frame_duration,
             reverse,
@@ -66,159 +115,560 @@ impl SpinnerState {
             text_color,
             dot_color,
             interval,
+            progress,
+            template,
+            prefix,
+            started,
+            paused_since: None,
+            paused_for: Duration::from_secs(0),
+            show_elapsed: false,
+            render_mode,
+            color_policy,
+            cursor_hidden: Arc::new(AtomicBool::new(false)),
+            announced: false,
+            frame_index: 0,
+            dot_count: 0,
+            frame_events: false,
+            #[cfg(feature = "tracing")]
+            message_before_spans: None,
         }
     }
 
-    pub fn update(&mut self, message: UpdateMessage) -> SpinnerResult<()> {
+    pub fn update(&self, message: UpdateMessage) -> SpinnerResult<()> {
+        let listeners = event_emitter::dispatching();
+        let message = match listeners.is_empty() {
+            true => SpinnerMessage::Update(message),
+            false => SpinnerMessage::ListenerUpdate(message, listeners),
+        };
         self.channel
-            .try_send(SpinnerMessage::Update(Ok(message)))
-            .map_err(|_| "Failed to send message through channel".into())
+            .try_send(message)
+            .map_err(|_| SpinnerError::ChannelClosed)
     }
 
     pub fn stop(&self) -> SpinnerResult<()> {
         self.channel
             .try_send(SpinnerMessage::Stop)
-            .map_err(|_| "Failed to send stop message through channel".into())
+            .map_err(|_| SpinnerError::ChannelClosed)
+    }
+
+    pub fn request_suspend(&self, suspend: SuspendMessage) -> SpinnerResult<()> {
+        self.channel
+            .try_send(SpinnerMessage::Suspend(suspend))
+            .map_err(|_| SpinnerError::ChannelClosed)
+    }
+
+    /// Identifies the spinner across every copy of its state.
+    pub fn id(&self) -> usize {
+        self.id
+    }
+
+    pub fn message(&self) -> String {
+        format!("{}{}", self.text, self.dots)
+    }
+
+    pub fn colors(&self) -> (Option<Color>, Option<Color>, Option<Color>) {
+        (self.style_color, self.text_color, self.dot_color)
+    }
+
+    /// The emitter shared by every copy of this state, so that events from
+    /// the render thread reach the spinner's listeners.
+    pub fn emitter(&self) -> EventEmitter {
+        self.emitter.clone()
+    }
+
+    pub fn clock(&self) -> Arc<dyn Clock> {
+        self.clock.clone()
+    }
+
+    pub fn set_clock(&mut self, clock: Arc<dyn Clock>) {
+        self.progress.set_clock(clock.clone());
+        self.clock = clock;
+    }
+
+    pub fn config(&self) -> Arc<SpinnerConfig> {
+        self.config.clone()
+    }
+
+    pub fn set_config(&mut self, config: Arc<SpinnerConfig>) {
+        self.config = config;
+    }
+
+    pub fn output(&self) -> Arc<Mutex<SpinnerStream>> {
+        self.output.clone()
+    }
+
+    pub fn color_policy(&self) -> ColorPolicy {
+        self.color_policy
+    }
+
+    pub fn render_mode(&self) -> RenderMode {
+        self.render_mode
     }
 
     pub fn set_reverse(&mut self, reverse: bool) {
         self.reverse.store(reverse, Ordering::SeqCst);
     }
 
+    pub fn finish(&self, finish: FinishMessage, outcome: Outcome) -> SpinnerResult<()> {
+        self.channel
+            .try_send(SpinnerMessage::Finish(finish, outcome))
+            .map_err(|_| SpinnerError::ChannelClosed)
+    }
+
     pub fn spin(
         &mut self,
         running: Arc<AtomicBool>,
         paused: Arc<(Mutex<bool>, Condvar)>,
     ) -> SpinnerResult<()> {
-        write!(self.output.lock().unwrap(), "\x1B[?25l")
-            .map_err(|e| SpinnerError::new(&e.to_string()))?; // hide cursor
+        let result = self.run(running, paused);
+        let restored = self.restore();
+        self.report(result.and(restored))
+    }
+
+    /// Emits an error event for a failed write, passing the result through.
+    pub fn report(&self, result: SpinnerResult<()>) -> SpinnerResult<()> {
+        if let Err(SpinnerError::Io(error)) = &result {
+            self.emitter.emit(&SpinnerEvent::Error {
+                kind: error.kind(),
+                message: error.to_string(),
+            });
+        }
+        result
+    }
 
-        let mut dot_count = self.dots.len();
-        let mut current_index = 0;
+    /// Emits the finish of a spinner stopped by an interrupt rather than by
+    /// its render loop.
+    pub fn interrupted(&self, message: Option<&str>) {
+        let symbol = match message {
+            Some(_) => self.config.symbols.failure(),
+            None => "",
+        };
+        self.emitter.emit(&SpinnerEvent::Finish {
+            symbol: symbol.to_string(),
+            message: message.unwrap_or_default().to_string(),
+            outcome: Outcome::Cancelled,
+        });
+    }
 
-        loop {
-            if !running.load(Ordering::SeqCst) {
-                break;
+    fn run(
+        &mut self,
+        running: Arc<AtomicBool>,
+        paused: Arc<(Mutex<bool>, Condvar)>,
+    ) -> SpinnerResult<()> {
+        self.begin();
+
+        let result = loop {
+            {
+                let (lock, cvar) = &*paused;
+                let mut paused = lock.lock().unwrap();
+
+                if *paused {
+                    self.set_paused(true);
+                    self.clock.idle();
+                    while *paused {
+                        paused = cvar.wait(paused).unwrap();
+                    }
+                    self.set_paused(false);
+                }
             }
 
-            let (lock, cvar) = &*paused;
-            let mut paused = lock.lock().unwrap();
+            if !running.load(Ordering::SeqCst) {
+                break self.drain_pending();
+            }
 
-            while *paused {
-                paused = cvar.wait(paused).unwrap();
+            if let Some(result) = self.tick() {
+                break result;
             }
 
-            let frames = &self.frames;
-            let frames_length = frames.len();
-            let frame = &frames[current_index % frames_length];
-            let dots = ".".repeat(dot_count.min(self.dots.len()));
+            self.clock.sleep(self.frame_interval());
+        };
 
-            self.print(frame, &self.text, &dots)?;
+        self.clock.idle();
+        result
+    }
 
-            current_index = match self.reverse.load(Ordering::SeqCst) {
-                true => (current_index + frames_length - 1) % frames_length,
-                false => (current_index + 1) % frames_length,
-            };
+    pub fn begin(&mut self) {
+        self.started = Some(self.clock.now());
+        self.paused_since = None;
+        self.paused_for = Duration::from_secs(0);
+        self.frame_index = 0;
+        self.dot_count = self.dots.len();
+    }
 
-            dot_count = (dot_count + 1) % (frames_length * 4);
+    /// Applies pending messages and draws one frame. Returns the render
+    /// loop's result once it has to end.
+    pub fn tick(&mut self) -> Option<SpinnerResult<()>> {
+        if let Some(result) = self.drain_messages() {
+            return Some(result);
+        }
 
-            thread::sleep(
-                self.interval
-                    .unwrap_or(Duration::from_millis(self.frame_duration)),
-            );
+        let frames_length = self.frames.len();
+        let frame = self.frames[self.frame_index % frames_length].clone();
+        let dots = ".".repeat(self.dot_count.min(self.dots.len()));
 
-            if let Ok(spin_message) = self.channel.try_receive() {
-                match spin_message {
-                    SpinnerMessage::Stop => {
-                        if self.channel.try_send(SpinnerMessage::Stop).is_err() {
-                            return Err("Failed to send message through channel".into());
-                        }
-                        return Err("Spinner stopped".into());
+        if let Err(error) = self.draw(&frame, &dots) {
+            return Some(Err(error));
+        }
+        if self.frame_events {
+            self.emitter.emit(&SpinnerEvent::Frame {
+                index: self.frame_index % frames_length,
+                frame,
+            });
+        }
+
+        self.frame_index = match self.reverse.load(Ordering::SeqCst) {
+            true => (self.frame_index + frames_length - 1) % frames_length,
+            false => (self.frame_index + 1) % frames_length,
+        };
+
+        self.dot_count = (self.dot_count + 1) % (frames_length * 4);
+        None
+    }
+
+    /// Tracks pauses so that the elapsed time shown excludes them.
+    pub fn set_paused(&mut self, paused: bool) {
+        match (paused, self.paused_since) {
+            (true, None) => self.paused_since = Some(self.clock.now()),
+            (false, Some(paused_since)) => {
+                self.paused_for += self.clock.now().saturating_duration_since(paused_since);
+                self.paused_since = None;
+            }
+            _ => {}
+        }
+    }
+
+    fn active_elapsed(&self) -> Duration {
+        let Some(started) = self.started else {
+            return Duration::from_secs(0);
+        };
+        let now = self.clock.now();
+        let paused = match self.paused_since {
+            Some(paused_since) => self.paused_for + now.saturating_duration_since(paused_since),
+            None => self.paused_for,
+        };
+        now.saturating_duration_since(started)
+            .saturating_sub(paused)
+    }
+
+    pub fn frame_interval(&self) -> Duration {
+        self.interval
+            .unwrap_or(Duration::from_millis(self.frame_duration))
+    }
+
+    /// The spinner may have been stopped before the render loop picked up
+    /// the pending messages, so a final state still has to be printed.
+    pub fn drain_pending(&mut self) -> SpinnerResult<()> {
+        self.drain_messages().unwrap_or(Ok(()))
+    }
+
+    /// Applies every pending message. Returns the loop's result once a stop or
+    /// finish message has been handled.
+    fn drain_messages(&mut self) -> Option<SpinnerResult<()>> {
+        while let Ok(spin_message) = self.channel.try_receive() {
+            match spin_message {
+                SpinnerMessage::Stop => {
+                    self.emitter.emit(&SpinnerEvent::Finish {
+                        symbol: String::new(),
+                        message: self.message(),
+                        outcome: Outcome::Cancelled,
+                    });
+                    return Some(Ok(()));
+                }
+                SpinnerMessage::Finish(finish, outcome) => {
+                    let result = self.print_final(&finish);
+                    self.emitter.emit(&SpinnerEvent::Finish {
+                        symbol: finish.symbol,
+                        message: finish.text,
+                        outcome,
+                    });
+                    return Some(result);
+                }
+                SpinnerMessage::Suspend(suspend) => {
+                    if let Err(error) = self.suspend(suspend) {
+                        return Some(Err(error));
+                    }
+                }
+                SpinnerMessage::Update(update) => {
+                    if let Err(error) = self.apply(update) {
+                        return Some(Err(error));
                     }
-                    SpinnerMessage::Update(result) => match result {
-                        Ok(UpdateMessage::Message(mesage)) => {
-                            let (text, dots) = trim_trailing_dots(mesage);
-                            self.text = text;
-                            self.dots = dots;
-                            dot_count = 0;
-                        }
-                        Ok(UpdateMessage::Style(spinner_style)) => {
-                            self.spinner_style = spinner_style;
-                            let data = get_spinner_data(&self.spinner_style);
-                            self.frames = data.frames;
-                            self.frame_duration = data.frame_duration;
-                            current_index = 0;
-                        }
-                        Ok(UpdateMessage::Alignment(alignment)) => {
-                            self.alignment = alignment;
-                        }
-                        Ok(UpdateMessage::Colors(style_color, text_color, dot_color)) => {
-                            self.style_color = style_color;
-                            self.text_color = text_color;
-                            self.dot_color = dot_color;
-                        }
-                        Ok(UpdateMessage::FramesPerSecond(fps)) => {
-                            let frame_duration = 1.0 / fps;
-                            let duration = Duration::from_secs_f64(frame_duration);
-                            self.interval = Some(duration);
-                        }
-                        Ok(UpdateMessage::Speed(rpm)) => {
-                            const SECONDS_PER_MINUTE: f64 = 60.0;
-                            let duration = Duration::from_secs_f64(SECONDS_PER_MINUTE / rpm)
-                                / self.frames.len() as u32;
-                            self.interval = Some(duration);
-                        }
-                        Ok(UpdateMessage::Frames(frames)) => {
-                            self.frames = frames;
-                            current_index = 0;
-                            dot_count = 0;
-                        }
-                        Ok(UpdateMessage::Stream(output)) => {
-                            self.output = Arc::new(Mutex::new(output));
-                        }
-                        Err(_) => return Err("Failed to receive update message".into()),
-                    },
                 }
+                SpinnerMessage::ListenerUpdate(update, listeners) => {
+                    let applied = event_emitter::dispatching_as(&listeners, || self.apply(update));
+                    if let Err(error) = applied {
+                        return Some(Err(error));
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    /// Applies a single update to the state and emits the matching change
+    /// event.
+    pub fn apply(&mut self, update: UpdateMessage) -> SpinnerResult<()> {
+        match update {
+            UpdateMessage::Message(mesage) => {
+                let old = self.message();
+                let (text, dots) = trim_trailing_dots(mesage);
+                self.text = text;
+                self.dots = dots;
+                self.announced = false;
+                self.dot_count = 0;
+                self.emitter.emit(&SpinnerEvent::MessageChange {
+                    old,
+                    new: self.message(),
+                });
+            }
+            UpdateMessage::Style(style, data) => {
+                let (style, data) = self.config.style_or(style, data);
+                self.frames = data.frames;
+                self.frame_duration = data.frame_duration;
+                self.frame_index = 0;
+                self.emitter.emit(&SpinnerEvent::StyleChange { style });
+            }
+            UpdateMessage::Alignment(alignment) => {
+                self.alignment = alignment;
+                self.emitter
+                    .emit(&SpinnerEvent::AlignmentChange { alignment });
+            }
+            UpdateMessage::Colors(style_color, text_color, dot_color) => {
+                self.style_color = style_color;
+                self.text_color = text_color;
+                self.dot_color = dot_color;
+                self.emitter.emit(&SpinnerEvent::ColorChange {
+                    spinner: style_color,
+                    message: text_color,
+                    dots: dot_color,
+                });
+            }
+            UpdateMessage::FramesPerSecond(fps) => {
+                let frame_duration = 1.0 / fps;
+                let duration = Duration::from_secs_f64(frame_duration);
+                self.interval = Some(duration);
+            }
+            UpdateMessage::Speed(rpm) => {
+                const SECONDS_PER_MINUTE: f64 = 60.0;
+                let duration =
+                    Duration::from_secs_f64(SECONDS_PER_MINUTE / rpm) / self.frames.len() as u32;
+                self.interval = Some(duration);
+            }
+            UpdateMessage::Frames(frames) => {
+                self.frames = frames.clone();
+                self.frame_index = 0;
+                self.dot_count = 0;
+                self.emitter.emit(&SpinnerEvent::FramesChange { frames });
+            }
+            UpdateMessage::Stream(output) => {
+                self.restore()?;
+                *self.output.lock().unwrap() = output;
+            }
+            UpdateMessage::Progress(update) => {
+                self.progress.apply(update);
+            }
+            UpdateMessage::Template(template) => {
+                self.template = template;
+            }
+            UpdateMessage::Prefix(prefix) => {
+                self.prefix = prefix;
+            }
+            UpdateMessage::ShowElapsed(show_elapsed) => {
+                self.show_elapsed = show_elapsed;
+            }
+            UpdateMessage::FrameEvents(frame_events) => {
+                self.frame_events = frame_events;
+            }
+            UpdateMessage::ColorPolicy(color_policy) => {
+                self.color_policy = color_policy;
+            }
+            UpdateMessage::RenderMode(render_mode) => {
+                self.restore()?;
+                self.render_mode = self.config.render_mode_or(render_mode);
+            }
+            #[cfg(feature = "tracing")]
+            UpdateMessage::SpanName(Some(name)) => {
+                if self.message_before_spans.is_none() {
+                    self.message_before_spans = Some(self.message());
+                }
+                self.apply(UpdateMessage::Message(name))?;
+            }
+            #[cfg(feature = "tracing")]
+            UpdateMessage::SpanName(None) => {
+                if let Some(message) = self.message_before_spans.take() {
+                    self.apply(UpdateMessage::Message(message))?;
+                }
+            }
+        }
+        Ok(())
+    }
+
+    fn active_render_mode(&self) -> RenderMode {
+        self.render_mode
+            .resolve(|| self.output.lock().unwrap().is_terminal())
+    }
+
+    fn colors_enabled(&self) -> bool {
+        self.color_policy
+            .should_colorize(|| self.output.lock().unwrap().is_terminal())
+    }
+
+    /// Strips every escape, including ones embedded in user messages, when
+    /// colors are disabled.
+    fn colorize<'a>(&self, colors_enabled: bool, line: &'a str) -> Cow<'a, str> {
+        match colors_enabled {
+            true => Cow::Borrowed(line),
+            false => strip_ansi(line),
+        }
+    }
+
+    fn draw(&mut self, frame: &str, dots: &str) -> SpinnerResult<()> {
+        match self.active_render_mode() {
+            RenderMode::Animated => {
+                if !self.cursor_hidden.load(Ordering::SeqCst) {
+                    write!(self.output.lock().unwrap(), "\x1B[?25l")?; // hide cursor
+                    self.cursor_hidden.store(true, Ordering::SeqCst);
+                }
+                self.print(frame, &self.text, dots)
+            }
+            RenderMode::Plain if !self.announced => {
+                self.announced = true;
+                let line = format!("{}{}", self.text, self.dots);
+                let line = self.colorize(self.colors_enabled(), &line).into_owned();
+                let mut w = self.output.lock().unwrap();
+                writeln!(w, "{}", line)?;
+                w.flush().map_err(SpinnerError::Io)
             }
+            _ => Ok(()),
+        }
+    }
+
+    fn suspend(&mut self, suspend: SuspendMessage) -> SpinnerResult<()> {
+        self.clear_line()?;
+        // Either side may have given up already; the line is redrawn on the
+        // next tick in any case.
+        if suspend.acquired.send(()).is_ok() {
+            let _ = suspend.released.recv();
         }
         Ok(())
     }
 
+    pub fn clear_line(&self) -> SpinnerResult<()> {
+        if !self.cursor_hidden.load(Ordering::SeqCst) {
+            return Ok(());
+        }
+        let mut w = self.output.lock().unwrap();
+        write!(w, "\r\x1B[K")?;
+        w.flush().map_err(SpinnerError::Io)
+    }
+
+    /// Gives up the drawn line, if any, so that whoever clears it also shows
+    /// the cursor again. Returns whether a line was drawn.
+    pub fn take_line(&self) -> bool {
+        self.cursor_hidden.swap(false, Ordering::SeqCst)
+    }
+
+    pub fn restore(&mut self) -> SpinnerResult<()> {
+        if !self.cursor_hidden.swap(false, Ordering::SeqCst) {
+            return Ok(());
+        }
+
+        let mut w = self.output.lock().unwrap();
+        write!(w, "\r\x1B[K\x1B[?25h")?; // show cursor
+        w.flush().map_err(SpinnerError::Io)
+    }
+
+    fn print_final(&self, finish: &FinishMessage) -> SpinnerResult<()> {
+        let render_mode = self.active_render_mode();
+        let colors_enabled = self.colors_enabled();
+        let mut w = self.output.lock().unwrap();
+        match render_mode {
+            RenderMode::Animated => {
+                let line = format!("{} {}", paint(&finish.symbol, finish.color), finish.text);
+                writeln!(w, "\r\x1B[K{}", self.colorize(colors_enabled, &line))
+            }
+            RenderMode::Plain => {
+                let line = format!("{} {}", finish.symbol, finish.text);
+                writeln!(w, "{}", self.colorize(colors_enabled, &line))
+            }
+            _ => return Ok(()),
+        }?;
+        w.flush().map_err(SpinnerError::Io)
+    }
+
     fn print(&self, frame: &str, text: &str, dots: &str) -> SpinnerResult<()> {
         let (width, _) = get_terminal_size();
-        let padding_str = self.alignment.get_horizontal_padding(
-            width - 2,
-            frame.width() + self.text.width() + self.dots.width(),
-        );
 
-        let colored_frame = match self.style_color {
-            Some(color) => frame.color(color).to_string(),
-            None => frame.to_owned(),
+        let (body, body_width) = match &self.template {
+            Some(template) => self.render_template(template, frame, text, dots),
+            None => self.render_default(frame, text, dots),
         };
+        let body = self.colorize(self.colors_enabled(), &body);
 
-        let colored_text = match self.text_color {
-            Some(color) => text.color(color).to_string(),
-            None => text.to_owned(),
-        };
+        let padding_str = self.alignment.get_horizontal_padding(width - 2, body_width);
+
+        let output_str = format!("{}{}", padding_str, body);
 
-        let colored_dots = match self.dot_color {
-            Some(color) => dots.color(color).to_string(),
-            None => dots.to_owned(),
+        let mut w = self.output.lock().unwrap();
+        let clear_line = "\r\x1B[K";
+        write!(w, "{}{}", clear_line, output_str)?;
+        w.flush().map_err(SpinnerError::Io)
+    }
+
+    fn render_default(&self, frame: &str, text: &str, dots: &str) -> (String, usize) {
+        let mut progress_str = match self.progress.render() {
+            Some(progress) => format!(" {}", progress),
+            None => String::new(),
         };
+        if self.show_elapsed {
+            progress_str.push_str(&format!(" ({})", format_duration(self.active_elapsed())));
+        }
 
-        let output_str = format!(
-            "{}{} {}{}",
-            padding_str, colored_frame, colored_text, colored_dots
+        let colored_frame = paint(frame, self.style_color);
+        let colored_text = paint(text, self.text_color);
+        let colored_dots = paint(dots, self.dot_color);
+
+        let body = format!(
+            "{} {}{}{}",
+            colored_frame, colored_text, colored_dots, progress_str
         );
+        let body_width =
+            frame.width() + 1 + self.text.width() + self.dots.width() + progress_str.width();
 
-        let mut w = self.output.lock().unwrap();
-        let clear_line = "\r\x1B[K";
-        write!(w, "{}{}", clear_line, output_str).map_err(|e| SpinnerError::new(&e.to_string()))?;
-        w.flush().map_err(|e| SpinnerError::new(&e.to_string()))
+        (body, body_width)
+    }
+
+    fn render_template(
+        &self,
+        template: &Template,
+        frame: &str,
+        text: &str,
+        dots: &str,
+    ) -> (String, usize) {
+        let progress = &self.progress;
+        template.render(|key| match key {
+            Key::Spinner => (frame.to_owned(), self.style_color),
+            Key::Message => (text.to_owned(), self.text_color),
+            Key::Dots => (dots.to_owned(), self.dot_color),
+            Key::Prefix => (self.prefix.clone(), None),
+            Key::Elapsed => (format_duration(self.active_elapsed()), None),
+            Key::Bar => (progress.bar().unwrap_or_default(), None),
+            Key::Percent => {
+                let percent = progress.percent().map(|percent| format!("{}%", percent));
+                (percent.unwrap_or_default(), None)
+            }
+            Key::Position => (progress.format_position(), None),
+            Key::Total => (progress.format_total().unwrap_or_default(), None),
+            Key::Rate => (progress.format_rate().unwrap_or_default(), None),
+            Key::Eta => (
+                progress.eta().map(format_duration).unwrap_or_default(),
+                None,
+            ),
+        })
     }
 }
 
-fn trim_trailing_dots(message: impl Into<String>) -> (String, String) {
+pub(crate) fn trim_trailing_dots(message: impl Into<String>) -> (String, String) {
     let mut text = String::new();
     let mut message_dots = String::new();
 
@@ -241,11 +691,12 @@ fn get_terminal_size() -> (usize, usize) {
 }
 
 #[cfg(test)]
+#[allow(clippy::bool_assert_comparison)]
 mod tests {
     use std::thread;
 
     use super::*;
-    use crate::spinner::message::SpinnerMessage;
+    use crate::spinner::{message::SpinnerMessage, progress::ProgressUpdate};
 
     #[test]
     fn test_spinner_state_new() {
@@ -266,15 +717,15 @@ mod tests {
 
     #[test]
     fn test_update_spinner_state_with_message() {
-        let mut state = SpinnerState::new("Loading ...");
+        let state = SpinnerState::new("Loading ...");
         // Send an update message
         let update_message = UpdateMessage::Message("Updating...".to_string());
         let result = state.update(update_message);
         assert!(result.is_ok());
         // Receive the update message
         let spin_message = state.channel.try_receive().unwrap();
-        if let SpinnerMessage::Update(result) = spin_message {
-            if let Ok(UpdateMessage::Message(message)) = result {
+        if let SpinnerMessage::Update(update) = spin_message {
+            if let UpdateMessage::Message(message) = update {
                 // Ensure the received message matches the sent message
                 assert_eq!(message, "Updating...");
             } else {
@@ -285,6 +736,64 @@ mod tests {
         }
     }
 
+    #[test]
+    fn test_update_spinner_state_with_progress() {
+        let state = SpinnerState::new("Downloading");
+        let update = UpdateMessage::Progress(ProgressUpdate::Total(100));
+        assert!(state.update(update).is_ok());
+
+        let spin_message = state.channel.try_receive().unwrap();
+        assert!(matches!(
+            spin_message,
+            SpinnerMessage::Update(UpdateMessage::Progress(ProgressUpdate::Total(100)))
+        ));
+    }
+
+    #[test]
+    fn test_print_spinner_state_with_progress() {
+        let mut state = SpinnerState::new("Downloading");
+        state.progress.apply(ProgressUpdate::Total(100));
+        state.progress.apply(ProgressUpdate::Inc(40));
+        assert!(state.print("|", "Downloading", "").is_ok());
+    }
+
+    #[test]
+    fn test_render_default_with_elapsed() {
+        let mut state = SpinnerState::new("Loading");
+        state.show_elapsed = true;
+        state.begin();
+        state.color_policy = ColorPolicy::Never;
+        let (body, width) = state.render_default("|", "Loading", "");
+        assert_eq!(state.colorize(false, &body), "| Loading (0s)");
+        assert_eq!(width, "| Loading (0s)".len());
+    }
+
+    #[test]
+    fn test_active_elapsed_excludes_pauses() {
+        let mut state = SpinnerState::new("Loading");
+        state.begin();
+        state.started = Some(Instant::now() - Duration::from_secs(10));
+        state.paused_since = Some(Instant::now() - Duration::from_secs(4));
+        assert_eq!(state.active_elapsed().as_secs(), 6);
+
+        state.set_paused(false);
+        state.set_paused(false);
+        assert_eq!(state.active_elapsed().as_secs(), 6);
+        assert!(state.paused_since.is_none());
+    }
+
+    #[test]
+    fn test_print_spinner_state_with_template() {
+        let mut state = SpinnerState::new("Loading");
+        state.template = Some(Template::parse("{prefix} {spinner} {msg:.cyan}{dots}").unwrap());
+        state.prefix = "[1/3]".to_string();
+        assert!(state.print("|", "Text", "...").is_ok());
+
+        let template = state.template.as_ref().unwrap();
+        let (_, width) = state.render_template(template, "|", "Text", "...");
+        assert_eq!(width, "[1/3] | Text...".len());
+    }
+
     #[test]
     fn test_spinner_state_stop() {
         let spinner_state = SpinnerState::new("Loading ...");
@@ -298,6 +807,42 @@ mod tests {
         assert!(matches!(spin_message, SpinnerMessage::Stop))
     }
 
+    #[test]
+    fn test_spinner_state_finish() {
+        let spinner_state = SpinnerState::new("Loading ...");
+
+        let finish = FinishMessage {
+            symbol: "✔".to_string(),
+            text: "Done".to_string(),
+            color: Some(Color::Green),
+        };
+        assert!(spinner_state.finish(finish, Outcome::Success).is_ok());
+
+        let spin_message = spinner_state.channel.try_receive().unwrap();
+        if let SpinnerMessage::Finish(finish, outcome) = spin_message {
+            assert_eq!(outcome, Outcome::Success);
+            assert_eq!(finish.symbol, "✔");
+            assert_eq!(finish.text, "Done");
+        } else {
+            panic!("Expected a SpinnerMessage::Finish");
+        }
+    }
+
+    #[test]
+    fn test_spin_thread_prints_pending_finish() {
+        let running = Arc::new(AtomicBool::new(false));
+        let paused = Arc::new((Mutex::new(false), Condvar::new()));
+        let mut state = SpinnerState::new("Loading ...");
+        let finish = FinishMessage {
+            symbol: "✔".to_string(),
+            text: "Done".to_string(),
+            color: None,
+        };
+        state.finish(finish, Outcome::Success).unwrap();
+        assert!(state.spin(running, paused).is_ok());
+        assert!(state.channel.try_receive().is_err());
+    }
+
     #[test]
     fn test_spin_thread() {
         let running = Arc::new(AtomicBool::new(true));
@@ -313,6 +858,82 @@ mod tests {
         spinner_thread.join().unwrap();
     }
 
+    #[test]
+    fn test_spin_thread_exits_on_stop_message() {
+        let running = Arc::new(AtomicBool::new(true));
+        let paused = Arc::new((Mutex::new(false), Condvar::new()));
+        let mut state = SpinnerState::new("Loading ...");
+        state.stop().unwrap();
+        assert!(state.spin(running, paused).is_ok());
+        assert!(state.channel.try_receive().is_err());
+    }
+
+    #[test]
+    fn test_draw_animated_hides_cursor() {
+        let mut state = SpinnerState::new("Loading ...");
+        state.render_mode = RenderMode::Animated;
+        state.draw("|", "...").unwrap();
+        assert!(state.cursor_hidden.load(Ordering::SeqCst));
+        state.restore().unwrap();
+        assert!(!state.cursor_hidden.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_draw_plain_announces_message_once() {
+        let mut state = SpinnerState::new("Loading ...");
+        state.render_mode = RenderMode::Plain;
+        state.draw("|", "...").unwrap();
+        assert!(state.announced);
+        assert!(!state.cursor_hidden.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_draw_hidden_writes_nothing() {
+        let mut state = SpinnerState::new("Loading ...");
+        state.render_mode = RenderMode::Hidden;
+        state.draw("|", "...").unwrap();
+        assert!(!state.announced);
+        assert!(!state.cursor_hidden.load(Ordering::SeqCst));
+    }
+
+    #[test]
+    fn test_colorize_never_strips_embedded_escapes() {
+        let mut state = SpinnerState::new("\x1B[31mLoading\x1B[0m");
+        state.color_policy = ColorPolicy::Never;
+        assert!(!state.colors_enabled());
+
+        let (body, _) = state.render_default("|", &state.text, "");
+        assert!(body.contains('\x1B'));
+        assert_eq!(state.colorize(state.colors_enabled(), &body), "| Loading");
+    }
+
+    #[test]
+    fn test_colorize_always_keeps_colors() {
+        let mut state = SpinnerState::new("Loading");
+        state.color_policy = ColorPolicy::Always;
+        let (body, _) = state.render_default("|", "Loading", "");
+        assert_eq!(state.colorize(state.colors_enabled(), &body), body);
+        assert!(body.starts_with("\x1B[35m|"));
+    }
+
+    #[cfg(feature = "tracing")]
+    #[test]
+    fn test_span_names_restore_original_message() {
+        let mut state = SpinnerState::new("Loading...");
+        state
+            .apply(UpdateMessage::SpanName(Some("fetch".into())))
+            .unwrap();
+        state
+            .apply(UpdateMessage::SpanName(Some("parse".into())))
+            .unwrap();
+        assert_eq!(state.message(), "parse");
+
+        state.apply(UpdateMessage::SpanName(None)).unwrap();
+        assert_eq!(state.message(), "Loading...");
+        state.apply(UpdateMessage::SpanName(None)).unwrap();
+        assert_eq!(state.message(), "Loading...");
+    }
+
     #[test]
     fn test_set_reverse_spinner_state() {
         let mut state = SpinnerState::new("Loading");
@@ -1,32 +1,84 @@
-use std::io::{self, Write};
+use std::fmt;
+use std::io::{self, IsTerminal, Write};
+use std::sync::{Arc, Mutex};
 
-#[derive(Debug, Clone)]
+use crate::SpinnerError;
+
+pub type CustomWriter = Box<dyn Write + Send>;
+
+#[derive(Clone)]
 pub enum SpinnerStream {
     Stdout,
     Stderr,
+    /// Any writer, such as a file, a pty or an in-memory buffer. Custom
+    /// writers are never treated as terminals.
+    Custom(Arc<Mutex<CustomWriter>>),
 }
 
-impl<'a> Into<SpinnerStream> for &'a str {
-    fn into(self) -> SpinnerStream {
+impl SpinnerStream {
+    pub fn custom<W>(writer: W) -> Self
+    where
+        W: Write + Send + 'static,
+    {
+        SpinnerStream::Custom(Arc::new(Mutex::new(Box::new(writer))))
+    }
+
+    pub fn is_terminal(&self) -> bool {
         match self {
-            "stdout" => SpinnerStream::Stdout,
-            "stderr" => SpinnerStream::Stderr,
-            _ => panic!("Invalid spinner stream"),
+            SpinnerStream::Stdout => io::stdout().is_terminal(),
+            SpinnerStream::Stderr => io::stderr().is_terminal(),
+            SpinnerStream::Custom(_) => false,
         }
     }
 }
 
+#[allow(clippy::derivable_impls)]
 impl Default for SpinnerStream {
     fn default() -> Self {
         Self::Stdout
     }
 }
 
+impl TryFrom<&str> for SpinnerStream {
+    type Error = SpinnerError;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value {
+            "stdout" => Ok(SpinnerStream::Stdout),
+            "stderr" => Ok(SpinnerStream::Stderr),
+            _ => Err(SpinnerError::InvalidStream(value.to_string())),
+        }
+    }
+}
+
+impl From<CustomWriter> for SpinnerStream {
+    fn from(writer: CustomWriter) -> Self {
+        SpinnerStream::Custom(Arc::new(Mutex::new(writer)))
+    }
+}
+
+impl fmt::Debug for SpinnerStream {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SpinnerStream::Stdout => write!(f, "Stdout"),
+            SpinnerStream::Stderr => write!(f, "Stderr"),
+            SpinnerStream::Custom(_) => write!(f, "Custom(..)"),
+        }
+    }
+}
+
+fn lock_writer(writer: &Mutex<CustomWriter>) -> std::sync::MutexGuard<'_, CustomWriter> {
+    writer
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+}
+
 impl Write for SpinnerStream {
     fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
         match self {
             SpinnerStream::Stdout => io::stdout().write(buf),
             SpinnerStream::Stderr => io::stderr().write(buf),
+            SpinnerStream::Custom(writer) => lock_writer(writer).write(buf),
         }
     }
 
@@ -34,6 +86,7 @@ impl Write for SpinnerStream {
         match self {
             SpinnerStream::Stdout => io::stdout().flush(),
             SpinnerStream::Stderr => io::stderr().flush(),
+            SpinnerStream::Custom(writer) => lock_writer(writer).flush(),
         }
     }
 }
@@ -41,6 +94,7 @@ impl Write for SpinnerStream {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::testing::CaptureWriter;
 
     #[test]
     fn test_spinner_stream_default() {
@@ -76,17 +130,35 @@ mod tests {
 
     #[test]
     fn test_str_into_spinner_stream_valid() {
-        let stdout: SpinnerStream = "stdout".into();
+        let stdout = SpinnerStream::try_from("stdout").unwrap();
         assert!(matches!(stdout, SpinnerStream::Stdout));
 
-        let stderr: SpinnerStream = "stderr".into();
+        let stderr = SpinnerStream::try_from("stderr").unwrap();
         assert!(matches!(stderr, SpinnerStream::Stderr));
     }
 
     #[test]
-    #[should_panic]
     fn test_str_into_spinner_stream_invalid() {
-        // Test an invalid stream, expecting a panic
-        let _ = Into::<SpinnerStream>::into("invalid");
+        let error = SpinnerStream::try_from("invalid").unwrap_err();
+        assert_eq!(error.to_string(), "Invalid spinner stream: invalid");
+    }
+
+    #[test]
+    fn test_spinner_stream_custom() {
+        let buffer = CaptureWriter::new();
+        let mut stream = SpinnerStream::custom(buffer.clone());
+        assert!(!stream.is_terminal());
+
+        write!(stream, "Hello").unwrap();
+        write!(stream.clone(), ", world!").unwrap();
+        assert!(stream.flush().is_ok());
+        assert_eq!(buffer.contents(), "Hello, world!");
+    }
+
+    #[test]
+    fn test_boxed_writer_into_spinner_stream() {
+        let writer: CustomWriter = Box::new(io::sink());
+        let stream = SpinnerStream::from(writer);
+        assert_eq!(format!("{:?}", stream), "Custom(..)");
     }
 }
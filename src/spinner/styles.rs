@@ -0,0 +1,204 @@
+use std::{collections::HashMap, fs, path::Path, sync::RwLock};
+
+use lazy_static::lazy_static;
+
+use super::builder::validate_frames;
+use super::builtins::{get_spinner_data, SpinnerData, SpinnerStyle};
+use crate::{SpinnerError, SpinnerResult};
+
+lazy_static! {
+    static ref CUSTOM_STYLES: RwLock<HashMap<String, SpinnerData>> = RwLock::new(HashMap::new());
+}
+
+/// A spinner style given either as a builtin or by the name of a style
+/// registered with [`register_style`] or loaded from a file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StyleRef {
+    Builtin(SpinnerStyle),
+    Named(String),
+}
+
+impl StyleRef {
+    /// Looks up the frames of the style. Registered styles take precedence
+    /// over builtins of the same name.
+    pub fn resolve(&self) -> SpinnerResult<SpinnerData> {
+        match self {
+            Self::Builtin(style) => Ok(get_spinner_data(style)),
+            Self::Named(name) => {
+                if let Some(data) = CUSTOM_STYLES.read().unwrap().get(name) {
+                    return Ok(data.clone());
+                }
+                let style: SpinnerStyle = name.parse()?;
+                Ok(get_spinner_data(&style))
+            }
+        }
+    }
+}
+
+impl From<SpinnerStyle> for StyleRef {
+    fn from(style: SpinnerStyle) -> Self {
+        Self::Builtin(style)
+    }
+}
+
+impl From<&str> for StyleRef {
+    fn from(name: &str) -> Self {
+        Self::Named(name.to_string())
+    }
+}
+
+impl From<String> for StyleRef {
+    fn from(name: String) -> Self {
+        Self::Named(name)
+    }
+}
+
+fn validate_style(name: &str, data: &SpinnerData) -> SpinnerResult<()> {
+    if name.is_empty() {
+        return Err(SpinnerError::config("Spinner style name must not be empty"));
+    }
+    validate_frames(&data.frames)?;
+    if data.frame_duration == 0 {
+        return Err(SpinnerError::config(format!(
+            "Invalid frame duration for spinner style: {}",
+            name
+        )));
+    }
+    Ok(())
+}
+
+/// Registers a named style that can be passed to `set_style`, replacing any
+/// style previously registered under that name.
+pub fn register_style(name: impl Into<String>, data: SpinnerData) -> SpinnerResult<()> {
+    let name = name.into();
+    validate_style(&name, &data)?;
+    CUSTOM_STYLES.write().unwrap().insert(name, data);
+    Ok(())
+}
+
+fn register_styles(styles: HashMap<String, SpinnerData>) -> SpinnerResult<usize> {
+    for (name, data) in &styles {
+        validate_style(name, data)?;
+    }
+    let count = styles.len();
+    CUSTOM_STYLES.write().unwrap().extend(styles);
+    Ok(count)
+}
+
+/// Registers every style of a TOML file laid out like the builtin
+/// `frames.toml`: one table per style with `frames` and `frame_duration`.
+/// Nothing is registered if any style is invalid. Returns the number of
+/// styles loaded.
+pub fn load_styles_from_toml(path: impl AsRef<Path>) -> SpinnerResult<usize> {
+    let path = path.as_ref();
+    let source = fs::read_to_string(path).map_err(|e| {
+        let message = format!("Failed to read spinner styles from {}", path.display());
+        SpinnerError::config_caused_by(message, e)
+    })?;
+    let styles = toml::from_str(&source).map_err(|e| {
+        let message = format!("Invalid spinner styles in {}", path.display());
+        SpinnerError::config_caused_by(message, e)
+    })?;
+    register_styles(styles)
+}
+
+/// Same as [`load_styles_from_toml`], from a JSON object mapping style names
+/// to `{ "frames": [...], "frame_duration": ... }`.
+pub fn load_styles_from_json(source: &str) -> SpinnerResult<usize> {
+    let styles = serde_json::from_str(source)
+        .map_err(|e| SpinnerError::config_caused_by("Invalid spinner styles", e))?;
+    register_styles(styles)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::env;
+
+    use super::*;
+
+    fn data(frames: &[&str], frame_duration: u64) -> SpinnerData {
+        SpinnerData {
+            frames: frames.iter().map(|frame| frame.to_string()).collect(),
+            frame_duration,
+        }
+    }
+
+    #[test]
+    fn test_resolve_builtin() {
+        let data = StyleRef::from("circle-halves").resolve().unwrap();
+        assert_eq!(data, get_spinner_data(&SpinnerStyle::CircleHalves));
+        let data = StyleRef::from(SpinnerStyle::Dots).resolve().unwrap();
+        assert_eq!(data, get_spinner_data(&SpinnerStyle::Dots));
+    }
+
+    #[test]
+    fn test_resolve_unknown() {
+        let result = StyleRef::from("test-unknown").resolve();
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Unknown spinner style: test-unknown"
+        );
+    }
+
+    #[test]
+    fn test_register_style() {
+        register_style("test-brand", data(&["a", "b"], 120)).unwrap();
+        let resolved = StyleRef::from("test-brand").resolve().unwrap();
+        assert_eq!(resolved, data(&["a", "b"], 120));
+    }
+
+    #[test]
+    fn test_register_style_rejects_invalid_data() {
+        assert!(register_style("test-empty", data(&[], 120)).is_err());
+        assert!(register_style("test-zero", data(&["a"], 0)).is_err());
+        assert!(register_style("", data(&["a"], 120)).is_err());
+        assert!(StyleRef::from("test-empty").resolve().is_err());
+    }
+
+    #[test]
+    fn test_load_styles_from_json() {
+        let source = r#"{
+            "test-json-a": { "frames": ["1", "2"], "frame_duration": 80 },
+            "test-json-b": { "frames": ["x"], "frame_duration": 40 }
+        }"#;
+        assert_eq!(load_styles_from_json(source).unwrap(), 2);
+        let resolved = StyleRef::from("test-json-b").resolve().unwrap();
+        assert_eq!(resolved, data(&["x"], 40));
+    }
+
+    #[test]
+    fn test_load_styles_is_all_or_nothing() {
+        let source = r#"{
+            "test-partial-a": { "frames": ["1"], "frame_duration": 80 },
+            "test-partial-b": { "frames": [], "frame_duration": 80 }
+        }"#;
+        assert!(load_styles_from_json(source).is_err());
+        assert!(StyleRef::from("test-partial-a").resolve().is_err());
+        assert!(load_styles_from_json("not json").is_err());
+    }
+
+    #[test]
+    fn test_load_styles_from_toml() {
+        let path = env::temp_dir().join(format!("spinergy-styles-{}.toml", std::process::id()));
+        fs::write(
+            &path,
+            "[test-toml]\nframe_duration = 90\nframes = [ \"-\", \"=\" ]\n",
+        )
+        .unwrap();
+        let result = load_styles_from_toml(&path);
+        fs::remove_file(&path).unwrap();
+
+        assert_eq!(result.unwrap(), 1);
+        let resolved = StyleRef::from("test-toml").resolve().unwrap();
+        assert_eq!(resolved, data(&["-", "="], 90));
+    }
+
+    #[test]
+    fn test_load_styles_from_missing_toml() {
+        let result = load_styles_from_toml("/nonexistent/spinergy/styles.toml");
+        assert!(result
+            .unwrap_err()
+            .to_string()
+            .starts_with("Failed to read spinner styles from"));
+    }
+}
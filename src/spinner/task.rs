@@ -0,0 +1,277 @@
+use std::sync::{
+    atomic::{AtomicBool, AtomicUsize, Ordering},
+    Arc, Condvar, Mutex,
+};
+use std::time::Duration;
+
+use tokio::runtime::Handle;
+use tokio::task::JoinHandle;
+use tokio::time::{self, Instant, Interval, MissedTickBehavior};
+
+use super::{
+    registry::{interrupt_line, is_rendering, lock, rendering, ActiveSpinner, SuspendGuard},
+    state::SpinnerState,
+};
+use crate::SpinnerResult;
+
+struct Shared {
+    state: Mutex<SpinnerState>,
+    /// A copy of the state to clear the line with while the task holds the
+    /// lock on `state`.
+    line: SpinnerState,
+    running: Arc<AtomicBool>,
+    paused: Arc<(Mutex<bool>, Condvar)>,
+    suspended: AtomicUsize,
+    result: Mutex<Option<SpinnerResult<()>>>,
+}
+
+/// A render loop driven by a tokio interval instead of a dedicated thread.
+///
+/// The task owns the state behind a mutex and never blocks while holding it,
+/// so stopping and suspending are done by locking the state from the caller.
+pub struct RenderTask {
+    task: JoinHandle<()>,
+    shared: Arc<Shared>,
+}
+
+impl RenderTask {
+    pub fn spawn(
+        runtime: &Handle,
+        state: SpinnerState,
+        running: Arc<AtomicBool>,
+        paused: Arc<(Mutex<bool>, Condvar)>,
+    ) -> (Self, Arc<dyn ActiveSpinner>) {
+        let shared = Arc::new(Shared {
+            line: state.clone(),
+            state: Mutex::new(state),
+            running,
+            paused,
+            suspended: AtomicUsize::new(0),
+            result: Mutex::new(None),
+        });
+        let task = runtime.spawn(render(shared.clone()));
+        let line = Arc::new(ActiveTask(shared.clone()));
+        (Self { task, shared }, line)
+    }
+
+    /// Stops the task and prints whatever final line is still pending. The
+    /// caller must have cleared the running flag.
+    pub fn finish(self) -> SpinnerResult<()> {
+        self.task.abort();
+
+        // Once the state is locked no tick is in flight, and any later tick
+        // observes the cleared running flag.
+        let mut state = lock(&self.shared.state);
+        if let Some(result) = lock(&self.shared.result).take() {
+            return result;
+        }
+        let result = state.drain_pending();
+        let restored = state.restore();
+        state.report(result.and(restored))
+    }
+}
+
+async fn render(shared: Arc<Shared>) {
+    let mut period = {
+        let mut state = lock(&shared.state);
+        state.begin();
+        state.frame_interval()
+    };
+    let mut interval = time::interval(period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+
+    loop {
+        interval.tick().await;
+        match rendering(shared.line.id(), || step(&shared)) {
+            Some(next) if next != period => {
+                period = next;
+                interval = delayed_interval(period);
+            }
+            Some(_) => {}
+            None => return,
+        }
+    }
+}
+
+fn delayed_interval(period: Duration) -> Interval {
+    let mut interval = time::interval_at(Instant::now() + period, period);
+    interval.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    interval
+}
+
+/// Draws one frame. Returns the interval until the next frame, or `None`
+/// once the render loop has to end.
+fn step(shared: &Shared) -> Option<Duration> {
+    let mut state = lock(&shared.state);
+    if !shared.running.load(Ordering::SeqCst) {
+        return None;
+    }
+    let paused = *lock(&shared.paused.0);
+    state.set_paused(paused);
+    if paused || shared.suspended.load(Ordering::SeqCst) > 0 {
+        return Some(state.frame_interval());
+    }
+    if let Some(result) = state.tick() {
+        let restored = state.restore();
+        *lock(&shared.result) = Some(state.report(result.and(restored)));
+        return None;
+    }
+    Some(state.frame_interval())
+}
+
+struct ActiveTask(Arc<Shared>);
+
+impl ActiveSpinner for ActiveTask {
+    fn interrupt(&self, message: Option<&str>) {
+        // A listener panicking within a step holds the lock on the state.
+        let state = match is_rendering(self.0.line.id()) {
+            true => None,
+            false => Some(lock(&self.0.state)),
+        };
+        let state = state.as_deref().unwrap_or(&self.0.line);
+        interrupt_line(state, &self.0.running, message);
+        state.interrupted(message);
+    }
+
+    fn suspend(&self) -> Option<SuspendGuard> {
+        if !self.0.running.load(Ordering::SeqCst) {
+            return None;
+        }
+        self.0.suspended.fetch_add(1, Ordering::SeqCst);
+        let resume = ResumeTask(self.0.clone());
+        // A listener runs within a step, which holds the lock on the state.
+        let _ = match is_rendering(self.0.line.id()) {
+            true => self.0.line.clear_line(),
+            false => lock(&self.0.state).clear_line(),
+        };
+        Some(SuspendGuard::new(resume))
+    }
+
+    #[cfg(feature = "tracing")]
+    fn show_span(&self, name: Option<&str>) {
+        let name = name.map(str::to_owned);
+        let _ = lock(&self.0.state).update(super::message::UpdateMessage::SpanName(name));
+    }
+}
+
+struct ResumeTask(Arc<Shared>);
+
+impl Drop for ResumeTask {
+    fn drop(&mut self) {
+        self.0.suspended.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+    use std::sync::OnceLock;
+
+    use crate::{testing::CaptureWriter, Event, RenderMode, Spinner};
+
+    use super::*;
+
+    fn animated_spinner(buffer: &CaptureWriter) -> Spinner {
+        let mut spinner = Spinner::new("Working");
+        spinner.set_output_stream(buffer.clone()).unwrap();
+        spinner.set_render_mode(RenderMode::Animated).unwrap();
+        spinner
+    }
+
+    #[test]
+    fn test_start_async_outside_runtime() {
+        let mut spinner = Spinner::new("Working");
+        assert!(spinner.start_async().is_err());
+        assert!(!spinner.is_running());
+    }
+
+    #[tokio::test]
+    async fn test_start_async_renders_and_finishes() {
+        let buffer = CaptureWriter::new();
+        let mut spinner = animated_spinner(&buffer);
+        spinner.start_async().unwrap();
+        assert!(spinner.start_async().is_err());
+        time::sleep(Duration::from_millis(100)).await;
+        spinner.succeed("Done").unwrap();
+        assert!(!spinner.is_running());
+
+        let output = buffer.contents();
+        assert!(output.contains("Working"));
+        assert!(output.ends_with("✔ Done\n\r\x1B[K\x1B[?25h"));
+    }
+
+    #[tokio::test]
+    async fn test_finish_before_first_tick() {
+        let buffer = CaptureWriter::new();
+        let mut spinner = Spinner::new("Working");
+        spinner.set_output_stream(buffer.clone()).unwrap();
+        spinner.start_async().unwrap();
+        spinner.fail("Failed").unwrap();
+        assert_eq!(buffer.contents(), "✖ Failed\n");
+    }
+
+    #[tokio::test]
+    async fn test_pause_and_suspend_skip_frames() {
+        let buffer = CaptureWriter::new();
+        let mut spinner = animated_spinner(&buffer);
+        spinner.start_async().unwrap();
+        time::sleep(Duration::from_millis(50)).await;
+
+        spinner.pause().unwrap();
+        let paused_at = buffer.contents().len();
+        time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(buffer.contents().len(), paused_at);
+        spinner.resume().unwrap();
+
+        spinner.println("log line").unwrap();
+        time::sleep(Duration::from_millis(100)).await;
+        spinner.stop().unwrap();
+
+        let output = buffer.contents();
+        let (before, after) = output.split_once("log line\n").unwrap();
+        assert!(before.ends_with("\r\x1B[K"));
+        assert!(after.contains("Working"));
+    }
+
+    #[tokio::test]
+    async fn test_listener_can_suspend_its_own_spinner() {
+        let buffer = CaptureWriter::new();
+        let mut spinner = animated_spinner(&buffer);
+        let mut output = buffer.clone();
+        let _subscription = spinner.on(Event::MessageChange, move |_| {
+            crate::suspend(|| writeln!(output, "log line").unwrap());
+        });
+        spinner.start_async().unwrap();
+        time::sleep(Duration::from_millis(50)).await;
+
+        spinner.set_message("Still working").unwrap();
+        time::sleep(Duration::from_millis(100)).await;
+        spinner.stop().unwrap();
+
+        let output = buffer.contents();
+        let (_, after) = output.split_once("\r\x1B[Klog line\n").unwrap();
+        assert!(after.contains("Still working"));
+    }
+
+    #[tokio::test]
+    async fn test_listener_can_interrupt_its_own_spinner() {
+        let buffer = CaptureWriter::new();
+        let mut spinner = animated_spinner(&buffer);
+        spinner.set_frame_events(true).unwrap();
+        // Stands in for the panic hook, which runs on the render task.
+        let line = Arc::new(OnceLock::<Arc<dyn ActiveSpinner>>::new());
+        let listener_line = line.clone();
+        let _subscription = spinner.on(Event::Frame, move |_| {
+            if let Some(line) = listener_line.get() {
+                line.interrupt(Some("Interrupted"));
+            }
+        });
+        spinner.start_async().unwrap();
+        let _ = line.set(spinner.line.clone().unwrap());
+        time::sleep(Duration::from_millis(100)).await;
+
+        assert!(!spinner.running.load(Ordering::SeqCst));
+        assert!(buffer.contents().contains("Interrupted\n"));
+        let _ = spinner.stop();
+    }
+}
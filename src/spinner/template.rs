@@ -0,0 +1,351 @@
+use std::str::FromStr;
+
+use colored::Color;
+use unicode_width::UnicodeWidthStr;
+
+use super::color::paint_with;
+use crate::{SpinnerError, SpinnerResult};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Key {
+    Spinner,
+    Message,
+    Dots,
+    Prefix,
+    Elapsed,
+    Bar,
+    Percent,
+    Position,
+    Total,
+    Rate,
+    Eta,
+}
+
+impl FromStr for Key {
+    type Err = SpinnerError;
+
+    fn from_str(value: &str) -> SpinnerResult<Self> {
+        match value {
+            "spinner" => Ok(Self::Spinner),
+            "msg" | "message" => Ok(Self::Message),
+            "dots" => Ok(Self::Dots),
+            "prefix" => Ok(Self::Prefix),
+            "elapsed" => Ok(Self::Elapsed),
+            "bar" => Ok(Self::Bar),
+            "percent" => Ok(Self::Percent),
+            "pos" => Ok(Self::Position),
+            "total" => Ok(Self::Total),
+            "rate" => Ok(Self::Rate),
+            "eta" => Ok(Self::Eta),
+            _ => Err(SpinnerError::InvalidTemplate(format!(
+                "Unknown template placeholder: {}",
+                value
+            ))),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Align {
+    Left,
+    Center,
+    Right,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Style {
+    Bold,
+    Dimmed,
+    Italic,
+    Underline,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Placeholder {
+    key: Key,
+    width: Option<usize>,
+    align: Align,
+    color: Option<Color>,
+    styles: Vec<Style>,
+}
+
+impl Placeholder {
+    fn parse(source: &str) -> SpinnerResult<Self> {
+        let (key, spec) = match source.split_once(':') {
+            Some((key, spec)) => (key, spec),
+            None => (source, ""),
+        };
+
+        let mut placeholder = Self {
+            key: key.trim().parse()?,
+            width: None,
+            align: Align::Left,
+            color: None,
+            styles: Vec::new(),
+        };
+
+        let mut modifiers = spec.split('.');
+        let layout = modifiers.next().unwrap_or_default();
+        let digits = match layout.chars().next() {
+            Some('<') => &layout[1..],
+            Some('^') => {
+                placeholder.align = Align::Center;
+                &layout[1..]
+            }
+            Some('>') => {
+                placeholder.align = Align::Right;
+                &layout[1..]
+            }
+            _ => layout,
+        };
+        if !digits.is_empty() {
+            let width = digits.parse().map_err(|_| {
+                SpinnerError::InvalidTemplate(format!("Invalid template width: {}", digits))
+            })?;
+            placeholder.width = Some(width);
+        }
+
+        for modifier in modifiers {
+            match modifier {
+                "bold" => placeholder.styles.push(Style::Bold),
+                "dim" | "dimmed" => placeholder.styles.push(Style::Dimmed),
+                "italic" => placeholder.styles.push(Style::Italic),
+                "underline" => placeholder.styles.push(Style::Underline),
+                color => {
+                    let color = Color::from_str(&color.replace('_', " ")).map_err(|_| {
+                        SpinnerError::InvalidTemplate(format!(
+                            "Unknown template modifier: {}",
+                            color
+                        ))
+                    })?;
+                    placeholder.color = Some(color);
+                }
+            }
+        }
+
+        Ok(placeholder)
+    }
+
+    fn pad(&self, value: String) -> String {
+        let width = match self.width {
+            Some(width) if width > value.width() => width - value.width(),
+            _ => return value,
+        };
+        match self.align {
+            Align::Left => format!("{}{}", value, " ".repeat(width)),
+            Align::Right => format!("{}{}", " ".repeat(width), value),
+            Align::Center => {
+                let left = width / 2;
+                format!("{}{}{}", " ".repeat(left), value, " ".repeat(width - left))
+            }
+        }
+    }
+
+    fn paint(&self, value: &str, default_color: Option<Color>) -> String {
+        let styles: Vec<&str> = self
+            .styles
+            .iter()
+            .map(|style| match style {
+                Style::Bold => "1",
+                Style::Dimmed => "2",
+                Style::Italic => "3",
+                Style::Underline => "4",
+            })
+            .collect();
+        paint_with(value, self.color.or(default_color), &styles)
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Part {
+    Literal(String),
+    Placeholder(Placeholder),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Template {
+    parts: Vec<Part>,
+}
+
+impl Template {
+    pub fn parse(source: &str) -> SpinnerResult<Self> {
+        let mut parts = Vec::new();
+        let mut literal = String::new();
+        let mut chars = source.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' if chars.peek() == Some(&'{') => {
+                    chars.next();
+                    literal.push('{');
+                }
+                '}' if chars.peek() == Some(&'}') => {
+                    chars.next();
+                    literal.push('}');
+                }
+                '{' => {
+                    let mut inner = String::new();
+                    loop {
+                        match chars.next() {
+                            Some('}') => break,
+                            Some('{') | None => {
+                                return Err(SpinnerError::InvalidTemplate(format!(
+                                    "Unclosed template placeholder: {{{}",
+                                    inner
+                                )))
+                            }
+                            Some(c) => inner.push(c),
+                        }
+                    }
+                    if !literal.is_empty() {
+                        parts.push(Part::Literal(std::mem::take(&mut literal)));
+                    }
+                    parts.push(Part::Placeholder(Placeholder::parse(&inner)?));
+                }
+                '}' => {
+                    return Err(SpinnerError::InvalidTemplate(
+                        "Unmatched '}' in template".to_string(),
+                    ))
+                }
+                c => literal.push(c),
+            }
+        }
+
+        if !literal.is_empty() {
+            parts.push(Part::Literal(literal));
+        }
+
+        Ok(Self { parts })
+    }
+
+    /// Renders the template, returning the output and its visible width.
+    pub(crate) fn render<F>(&self, mut value: F) -> (String, usize)
+    where
+        F: FnMut(Key) -> (String, Option<Color>),
+    {
+        let mut output = String::new();
+        let mut width = 0;
+
+        for part in &self.parts {
+            match part {
+                Part::Literal(text) => {
+                    width += text.width();
+                    output.push_str(text);
+                }
+                Part::Placeholder(placeholder) => {
+                    let (text, default_color) = value(placeholder.key);
+                    let text = placeholder.pad(text);
+                    width += text.width();
+                    output.push_str(&placeholder.paint(&text, default_color));
+                }
+            }
+        }
+
+        (output, width)
+    }
+}
+
+impl FromStr for Template {
+    type Err = SpinnerError;
+
+    fn from_str(source: &str) -> SpinnerResult<Self> {
+        Self::parse(source)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn render_plain(template: &Template) -> (String, usize) {
+        template.render(|key| {
+            let text = match key {
+                Key::Spinner => "◐",
+                Key::Message => "Loading",
+                Key::Dots => "...",
+                Key::Prefix => "[1/3]",
+                Key::Elapsed => "3s",
+                _ => "",
+            };
+            (text.to_string(), None)
+        })
+    }
+
+    #[test]
+    fn test_parse_literals_and_placeholders() {
+        let template = Template::parse("{spinner} [{elapsed}] {msg}{dots} {prefix}").unwrap();
+        let (output, width) = render_plain(&template);
+        assert_eq!(output, "◐ [3s] Loading... [1/3]");
+        assert_eq!(width, output.width());
+    }
+
+    #[test]
+    fn test_parse_escaped_braces() {
+        let template = Template::parse("{{{msg}}}").unwrap();
+        assert_eq!(render_plain(&template).0, "{Loading}");
+    }
+
+    #[test]
+    fn test_parse_modifiers() {
+        let template = Template::parse("{msg:>10.bright_cyan.bold}").unwrap();
+        let expected = Part::Placeholder(Placeholder {
+            key: Key::Message,
+            width: Some(10),
+            align: Align::Right,
+            color: Some(Color::BrightCyan),
+            styles: vec![Style::Bold],
+        });
+        assert_eq!(template.parts, vec![expected]);
+    }
+
+    #[test]
+    fn test_render_width_and_alignment() {
+        let template = Template::parse("|{msg:<9}|{msg:^9}|{msg:>9}|").unwrap();
+        assert_eq!(render_plain(&template).0, "|Loading  | Loading |  Loading|");
+    }
+
+    #[test]
+    fn test_render_applies_color() {
+        let template = Template::parse("{msg:.cyan}").unwrap();
+        let (output, width) = render_plain(&template);
+        assert_eq!(output, "\x1B[36mLoading\x1B[0m");
+        assert_eq!(width, 7);
+    }
+
+    #[test]
+    fn test_render_applies_styles() {
+        let template = Template::parse("{msg:.bold.underline}").unwrap();
+        let (output, _) = render_plain(&template);
+        assert_eq!(output, "\x1B[1;4mLoading\x1B[0m");
+    }
+
+    #[test]
+    fn test_parse_unknown_placeholder() {
+        let result = Template::parse("{nope}");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Unknown template placeholder: nope"
+        );
+    }
+
+    #[test]
+    fn test_parse_unknown_modifier() {
+        let result = Template::parse("{msg:.sparkly}");
+        assert_eq!(
+            result.unwrap_err().to_string(),
+            "Unknown template modifier: sparkly"
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_width() {
+        assert!(Template::parse("{msg:<x}").is_err());
+    }
+
+    #[test]
+    fn test_parse_unclosed_placeholder() {
+        assert!(Template::parse("{spinner {msg}").is_err());
+        assert!(Template::parse("{msg").is_err());
+        assert!(Template::parse("msg}").is_err());
+    }
+}
@@ -0,0 +1,580 @@
+//! Helpers to test code that drives a [`Spinner`](crate::Spinner) without
+//! real sleeps or a real terminal.
+//!
+//! A [`ManualClock`] passed to `SpinnerBuilder::clock` only moves when the
+//! test advances it, a [`CaptureWriter`] records everything the spinner
+//! writes, and a [`Screen`] replays that output the way a terminal would.
+//! Spinners made with [`builder`] start from an empty config, so the output
+//! does not depend on the `SPINERGY_*` variables or config file of whoever
+//! runs the tests.
+//! Custom streams are not terminals, so use `RenderMode::Animated` to see
+//! the animated line. Spinners started with `start_async` still wait on
+//! tokio's timer between frames, and a `MultiSpinner` has no clock of its
+//! own and always sleeps in real time.
+//!
+//! ```
+//! use std::time::Duration;
+//!
+//! use spinergy::testing::{self, CaptureWriter, ManualClock};
+//! use spinergy::RenderMode;
+//!
+//! let clock = ManualClock::new();
+//! let output = CaptureWriter::new();
+//! let mut spinner = testing::builder("Loading")
+//!     .frames(&["-", "+"])
+//!     .interval(Duration::from_millis(100))
+//!     .output_stream(output.clone())
+//!     .render_mode(RenderMode::Animated)
+//!     .clock(clock.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! spinner.start().unwrap();
+//! clock.settle();
+//! assert_eq!(output.screen().text(), "- Loading");
+//! clock.advance(Duration::from_millis(100));
+//! assert_eq!(output.screen().text(), "+ Loading");
+//!
+//! spinner.succeed("Done").unwrap();
+//! assert_eq!(output.screen().text(), "✔ Done");
+//! ```
+
+use std::collections::{HashMap, HashSet};
+use std::io::{self, Write};
+use std::sync::{Arc, Condvar, Mutex, MutexGuard, PoisonError};
+use std::thread::{self, ThreadId};
+use std::time::{Duration, Instant};
+
+pub use crate::clock::{Clock, SystemClock};
+use crate::{SpinnerBuilder, SpinnerStream};
+
+/// A spinner builder that ignores the end user's config, for output that is
+/// the same on every machine.
+pub fn builder(message: impl Into<String>) -> SpinnerBuilder {
+    SpinnerBuilder::new(message).ignore_config()
+}
+
+/// How long, in real time, [`ManualClock`] waits for render threads to draw
+/// their frame and go back to sleep.
+const SETTLE_TIMEOUT: Duration = Duration::from_secs(1);
+
+/// A clock that stands still until [`ManualClock::advance`] is called.
+///
+/// Clones share the same time, so keep one and pass another to the
+/// spinner.
+#[derive(Clone)]
+pub struct ManualClock {
+    inner: Arc<Shared>,
+}
+
+struct Shared {
+    start: Instant,
+    state: Mutex<ClockState>,
+    changed: Condvar,
+}
+
+#[derive(Default)]
+struct ClockState {
+    elapsed: Duration,
+    /// The deadline of every thread sleeping on the clock.
+    sleeping: HashMap<ThreadId, Duration>,
+    /// Threads woken by `advance` that have not gone back to sleep yet.
+    woken: HashSet<ThreadId>,
+    wakes: u64,
+}
+
+impl ClockState {
+    fn busy(&self) -> bool {
+        !self.woken.is_empty() || self.sleeping.values().any(|&due| due <= self.elapsed)
+    }
+}
+
+impl ManualClock {
+    pub fn new() -> Self {
+        Self {
+            inner: Arc::new(Shared {
+                start: Instant::now(),
+                state: Mutex::new(ClockState::default()),
+                changed: Condvar::new(),
+            }),
+        }
+    }
+
+    /// The virtual time since the clock was created.
+    pub fn elapsed(&self) -> Duration {
+        self.lock().elapsed
+    }
+
+    /// Moves time forward, stopping at every frame that falls due on the way
+    /// so that each one is drawn. Returns once every render thread has drawn
+    /// its frame and is asleep again.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a render thread has not gone back to sleep after a second
+    /// of real time, e.g. because it is blocked outside the clock.
+    pub fn advance(&self, duration: Duration) {
+        let mut state = self.lock();
+        let end = state.elapsed + duration;
+        loop {
+            let next = state
+                .sleeping
+                .values()
+                .copied()
+                .filter(|&due| due <= end)
+                .min();
+            state.elapsed = next.unwrap_or(end).max(state.elapsed);
+            self.inner.changed.notify_all();
+            state = self.wait_while(state, ClockState::busy);
+            if next.is_none() {
+                return;
+            }
+        }
+    }
+
+    /// Waits until a render thread sleeps on the clock, e.g. to see the
+    /// first frame of a spinner that was just started.
+    ///
+    /// # Panics
+    ///
+    /// Panics if no render thread sleeps on the clock within a second of
+    /// real time, e.g. because the spinner was never started.
+    pub fn settle(&self) {
+        let state = self.lock();
+        drop(self.wait_while(state, |state| state.sleeping.is_empty() || state.busy()));
+    }
+
+    fn wait_while<'a>(
+        &self,
+        mut state: MutexGuard<'a, ClockState>,
+        condition: impl Fn(&ClockState) -> bool,
+    ) -> MutexGuard<'a, ClockState> {
+        let deadline = Instant::now() + SETTLE_TIMEOUT;
+        while condition(&state) {
+            let Some(remaining) = deadline.checked_duration_since(Instant::now()) else {
+                panic!(
+                    "ManualClock: render threads did not go back to sleep within {:?}",
+                    SETTLE_TIMEOUT
+                );
+            };
+            state = self
+                .inner
+                .changed
+                .wait_timeout(state, remaining)
+                .unwrap_or_else(PoisonError::into_inner)
+                .0;
+        }
+        state
+    }
+
+    fn lock(&self) -> MutexGuard<'_, ClockState> {
+        self.inner
+            .state
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Default for ManualClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for ManualClock {
+    fn now(&self) -> Instant {
+        self.inner.start + self.lock().elapsed
+    }
+
+    fn sleep(&self, duration: Duration) {
+        let id = thread::current().id();
+        let mut state = self.lock();
+        state.woken.remove(&id);
+        let due = state.elapsed + duration;
+        let wakes = state.wakes;
+        state.sleeping.insert(id, due);
+        self.inner.changed.notify_all();
+
+        while state.elapsed < due && state.wakes == wakes {
+            state = self
+                .inner
+                .changed
+                .wait(state)
+                .unwrap_or_else(PoisonError::into_inner);
+        }
+        state.sleeping.remove(&id);
+        if state.wakes == wakes {
+            state.woken.insert(id);
+        }
+    }
+
+    fn wake(&self) {
+        let mut state = self.lock();
+        state.wakes += 1;
+        state.woken.clear();
+        self.inner.changed.notify_all();
+    }
+
+    fn idle(&self) {
+        let mut state = self.lock();
+        state.woken.remove(&thread::current().id());
+        self.inner.changed.notify_all();
+    }
+}
+
+/// An in-memory output stream. Clones share the same buffer.
+#[derive(Debug, Clone, Default)]
+pub struct CaptureWriter {
+    buffer: Arc<Mutex<Vec<u8>>>,
+}
+
+impl CaptureWriter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Everything written so far, escape sequences included.
+    pub fn contents(&self) -> String {
+        String::from_utf8_lossy(&self.lock()).into_owned()
+    }
+
+    /// What a terminal would show after everything written so far.
+    pub fn screen(&self) -> Screen {
+        let mut screen = Screen::new();
+        screen.feed(&self.contents());
+        screen
+    }
+
+    pub fn clear(&self) {
+        self.lock().clear();
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Vec<u8>> {
+        self.buffer.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+impl Write for CaptureWriter {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.lock().extend_from_slice(buf);
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+impl From<CaptureWriter> for SpinnerStream {
+    fn from(writer: CaptureWriter) -> Self {
+        SpinnerStream::custom(writer)
+    }
+}
+
+/// A minimal terminal that understands what spinners write: `\r`, `\n`,
+/// backspace, tabs, erasing lines and screens, moving the cursor and
+/// showing or hiding it. Colors and other escapes are dropped, and every
+/// character takes a single cell.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Screen {
+    rows: Vec<Vec<char>>,
+    row: usize,
+    column: usize,
+    cursor_hidden: bool,
+}
+
+impl Screen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn feed(&mut self, output: &str) {
+        let mut chars = output.chars().peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\r' => self.column = 0,
+                '\n' => {
+                    self.row += 1;
+                    self.column = 0;
+                }
+                '\x08' => self.column = self.column.saturating_sub(1),
+                '\t' => self.column = (self.column / 8 + 1) * 8,
+                '\x1B' if chars.peek() == Some(&'[') => {
+                    chars.next();
+                    let mut params = String::new();
+                    for c in chars.by_ref() {
+                        if ('@'..='~').contains(&c) {
+                            self.control(&params, c);
+                            break;
+                        }
+                        params.push(c);
+                    }
+                }
+                '\x1B' => {
+                    chars.next();
+                }
+                c if c.is_control() => {}
+                c => self.put(c),
+            }
+        }
+    }
+
+    /// The visible lines, without trailing blanks or empty lines at the
+    /// bottom.
+    pub fn lines(&self) -> Vec<String> {
+        let mut lines: Vec<String> = self
+            .rows
+            .iter()
+            .map(|row| row.iter().collect::<String>().trim_end().to_string())
+            .collect();
+        while lines.last().is_some_and(String::is_empty) {
+            lines.pop();
+        }
+        lines
+    }
+
+    /// The visible lines joined with newlines.
+    pub fn text(&self) -> String {
+        self.lines().join("\n")
+    }
+
+    /// The line at `row`, empty if nothing was drawn there.
+    pub fn line(&self, row: usize) -> String {
+        self.lines().get(row).cloned().unwrap_or_default()
+    }
+
+    /// The zero-based row and column of the cursor.
+    pub fn cursor(&self) -> (usize, usize) {
+        (self.row, self.column)
+    }
+
+    pub fn cursor_visible(&self) -> bool {
+        !self.cursor_hidden
+    }
+
+    fn put(&mut self, c: char) {
+        let column = self.column;
+        let row = self.row_mut();
+        if row.len() <= column {
+            row.resize(column + 1, ' ');
+        }
+        row[column] = c;
+        self.column += 1;
+    }
+
+    fn row_mut(&mut self) -> &mut Vec<char> {
+        if self.rows.len() <= self.row {
+            self.rows.resize(self.row + 1, Vec::new());
+        }
+        &mut self.rows[self.row]
+    }
+
+    fn control(&mut self, params: &str, command: char) {
+        if let Some(mode) = params.strip_prefix('?') {
+            match (mode, command) {
+                ("25", 'h') => self.cursor_hidden = false,
+                ("25", 'l') => self.cursor_hidden = true,
+                _ => {}
+            }
+            return;
+        }
+        let mut args = params.split(';').map(|arg| arg.parse::<usize>().ok());
+        let first = args.next().flatten();
+        let count = first.unwrap_or(1).max(1);
+        match command {
+            'A' => self.row = self.row.saturating_sub(count),
+            'B' => self.row += count,
+            'C' => self.column += count,
+            'D' => self.column = self.column.saturating_sub(count),
+            'G' => self.column = count - 1,
+            'H' | 'f' => {
+                self.row = count - 1;
+                self.column = args.next().flatten().unwrap_or(1).max(1) - 1;
+            }
+            'K' => self.erase_line(first.unwrap_or(0)),
+            'J' => self.erase_screen(first.unwrap_or(0)),
+            _ => {}
+        }
+    }
+
+    fn erase_line(&mut self, mode: usize) {
+        let column = self.column;
+        let row = self.row_mut();
+        match mode {
+            0 => row.truncate(column),
+            1 => row.iter_mut().take(column + 1).for_each(|cell| *cell = ' '),
+            _ => row.clear(),
+        }
+    }
+
+    fn erase_screen(&mut self, mode: usize) {
+        match mode {
+            0 => {
+                self.erase_line(0);
+                self.rows.truncate(self.row + 1);
+            }
+            1 => {
+                self.erase_line(1);
+                self.rows
+                    .iter_mut()
+                    .take(self.row)
+                    .for_each(|row| row.clear());
+            }
+            _ => self.rows.iter_mut().for_each(|row| row.clear()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{RenderMode, Spinner};
+
+    use super::*;
+
+    fn feed(output: &str) -> Screen {
+        let mut screen = Screen::new();
+        screen.feed(output);
+        screen
+    }
+
+    #[test]
+    fn test_screen_overwrites_line_on_carriage_return() {
+        let screen = feed("\x1B[?25l\r\x1B[K- Loading\r\x1B[K+ Load");
+        assert_eq!(screen.text(), "+ Load");
+        assert_eq!(screen.cursor(), (0, 6));
+        assert!(!screen.cursor_visible());
+    }
+
+    #[test]
+    fn test_screen_ignores_colors() {
+        let screen = feed("\x1B[35m-\x1B[0m Loading\n\x1B[32m✔\x1B[0m Done\n\x1B[?25h");
+        assert_eq!(screen.lines(), vec!["- Loading", "✔ Done"]);
+        assert!(screen.cursor_visible());
+    }
+
+    #[test]
+    fn test_screen_moves_cursor_and_erases() {
+        let screen = feed("one\ntwo\nthree\n\x1B[2A\r\x1B[Jfour");
+        assert_eq!(screen.lines(), vec!["one", "four"]);
+
+        let screen = feed("abcdef\x1B[3D\x1B[K\x1B[1;2Hx");
+        assert_eq!(screen.text(), "axc");
+
+        let screen = feed("abcdef\x1B[3G\x1B[1K");
+        assert_eq!(screen.text(), "   def");
+    }
+
+    #[test]
+    fn test_capture_writer_is_shared() {
+        let writer = CaptureWriter::new();
+        let mut clone = writer.clone();
+        write!(clone, "\r\x1B[Kdone").unwrap();
+        assert_eq!(writer.contents(), "\r\x1B[Kdone");
+        assert_eq!(writer.screen().text(), "done");
+        writer.clear();
+        assert_eq!(writer.contents(), "");
+    }
+
+    #[test]
+    fn test_manual_clock_sleep_waits_for_advance() {
+        let clock = ManualClock::new();
+        let start = clock.now();
+        let sleeper = clock.clone();
+        let (tx, rx) = crossbeam::channel::unbounded();
+        let handle = thread::spawn(move || {
+            sleeper.sleep(Duration::from_secs(60));
+            tx.send(sleeper.now()).unwrap();
+            sleeper.sleep(Duration::from_secs(60));
+        });
+
+        clock.settle();
+        assert!(rx.try_recv().is_err());
+        // Returns once the sleeper is asleep again.
+        clock.advance(Duration::from_secs(60));
+        assert_eq!(rx.try_recv().unwrap() - start, Duration::from_secs(60));
+        assert_eq!(clock.elapsed(), Duration::from_secs(60));
+
+        clock.wake();
+        handle.join().unwrap();
+    }
+
+    #[test]
+    fn test_manual_clock_wake() {
+        let clock = ManualClock::new();
+        let sleeper = clock.clone();
+        let handle = thread::spawn(move || sleeper.sleep(Duration::from_secs(60)));
+        clock.settle();
+        clock.wake();
+        handle.join().unwrap();
+        assert_eq!(clock.elapsed(), Duration::ZERO);
+    }
+
+    #[test]
+    #[should_panic(expected = "did not go back to sleep")]
+    fn test_settle_panics_without_render_thread() {
+        ManualClock::new().settle();
+    }
+
+    fn spinner(clock: &ManualClock, output: &CaptureWriter) -> Spinner {
+        builder("Loading")
+            .frames(&["a", "b", "c"])
+            .interval(Duration::from_millis(100))
+            .output_stream(output.clone())
+            .render_mode(RenderMode::Animated)
+            .clock(clock.clone())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_step_through_frames() {
+        let clock = ManualClock::new();
+        let output = CaptureWriter::new();
+        let mut spinner = spinner(&clock, &output);
+
+        spinner.start().unwrap();
+        clock.settle();
+        assert_eq!(output.screen().text(), "a Loading");
+        assert!(!output.screen().cursor_visible());
+
+        clock.advance(Duration::from_millis(100));
+        assert_eq!(output.screen().text(), "b Loading");
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(output.screen().text(), "b Loading");
+        clock.advance(Duration::from_millis(50));
+        assert_eq!(output.screen().text(), "c Loading");
+
+        // Every frame on the way is drawn.
+        output.clear();
+        clock.advance(Duration::from_millis(300));
+        assert_eq!(output.contents().matches("Loading").count(), 3);
+
+        spinner.succeed("Done").unwrap();
+        let screen = output.screen();
+        assert_eq!(screen.text(), "✔ Done");
+        assert!(screen.cursor_visible());
+    }
+
+    #[test]
+    fn test_elapsed_follows_clock() {
+        let clock = ManualClock::new();
+        let output = CaptureWriter::new();
+        let mut spinner = spinner(&clock, &output);
+        spinner.set_show_elapsed(true).unwrap();
+
+        spinner.start().unwrap();
+        clock.settle();
+        clock.advance(Duration::from_secs(3));
+        assert_eq!(spinner.elapsed(), Duration::from_secs(3));
+        assert!(output.screen().text().ends_with("Loading (3s)"));
+
+        spinner.pause().unwrap();
+        clock.advance(Duration::from_secs(5));
+        spinner.resume().unwrap();
+        assert_eq!(spinner.active_elapsed(), Duration::from_secs(3));
+
+        spinner.stop().unwrap();
+        assert_eq!(spinner.elapsed(), Duration::from_secs(8));
+        assert_eq!(output.screen().text(), "");
+    }
+}
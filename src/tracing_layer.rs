@@ -0,0 +1,175 @@
+use std::fmt::{self, Write as _};
+use std::io::Write;
+use std::sync::Mutex;
+
+use colored::Color;
+use tracing_core::field::{Field, Visit};
+use tracing_core::span::Id;
+use tracing_core::{Event, Level, Subscriber};
+use tracing_subscriber::layer::{Context, Layer};
+use tracing_subscriber::registry::LookupSpan;
+
+use crate::spinner::color::{paint, ColorPolicy};
+use crate::spinner::registry::{lock, ACTIVE_SPINNERS};
+use crate::SpinnerStream;
+
+/// A `tracing_subscriber` layer that prints events above the active spinners
+/// and optionally shows the latest entered span as the spinner message.
+pub struct SpinnerLayer {
+    output: Mutex<SpinnerStream>,
+    color_policy: ColorPolicy,
+    show_span_names: bool,
+}
+
+impl SpinnerLayer {
+    pub fn new() -> Self {
+        Self {
+            output: Mutex::new(SpinnerStream::Stderr),
+            color_policy: ColorPolicy::default(),
+            show_span_names: false,
+        }
+    }
+
+    pub fn output_stream(mut self, stream: SpinnerStream) -> Self {
+        self.output = Mutex::new(stream);
+        self
+    }
+
+    pub fn color_policy<T>(mut self, color_policy: T) -> Self
+    where
+        T: Into<ColorPolicy>,
+    {
+        self.color_policy = color_policy.into();
+        self
+    }
+
+    /// Sets the message of every active spinner to the name of the span being
+    /// entered, back to its parent's name when the span is exited, and back to
+    /// the original message when the outermost span is exited. Children of a
+    /// `MultiSpinner` keep their own messages.
+    pub fn show_span_names(mut self, show_span_names: bool) -> Self {
+        self.show_span_names = show_span_names;
+        self
+    }
+
+    fn write_event(&self, event: &Event<'_>) {
+        let mut visitor = EventVisitor::default();
+        event.record(&mut visitor);
+
+        let mut w = lock(&self.output);
+        let level = *event.metadata().level();
+        let color = match level {
+            Level::ERROR => Color::Red,
+            Level::WARN => Color::Yellow,
+            Level::INFO => Color::Green,
+            Level::DEBUG => Color::Blue,
+            Level::TRACE => Color::Magenta,
+        };
+        let level = format!("{:<5}", level);
+        let level = match self.color_policy.should_colorize(|| w.is_terminal()) {
+            true => paint(&level, Some(color)),
+            false => level,
+        };
+        let _ = writeln!(w, "{} {}{}", level, visitor.message, visitor.fields);
+        let _ = w.flush();
+    }
+}
+
+impl Default for SpinnerLayer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> Layer<S> for SpinnerLayer
+where
+    S: Subscriber + for<'lookup> LookupSpan<'lookup>,
+{
+    fn on_event(&self, event: &Event<'_>, _ctx: Context<'_, S>) {
+        ACTIVE_SPINNERS.suspend_all(|| self.write_event(event));
+    }
+
+    fn on_enter(&self, id: &Id, ctx: Context<'_, S>) {
+        if !self.show_span_names {
+            return;
+        }
+        if let Some(span) = ctx.span(id) {
+            ACTIVE_SPINNERS.show_span_all(Some(span.name()));
+        }
+    }
+
+    fn on_exit(&self, id: &Id, ctx: Context<'_, S>) {
+        if !self.show_span_names {
+            return;
+        }
+        let parent = ctx.span(id).and_then(|span| span.parent());
+        ACTIVE_SPINNERS.show_span_all(parent.as_ref().map(|parent| parent.name()));
+    }
+}
+
+#[derive(Default)]
+struct EventVisitor {
+    message: String,
+    fields: String,
+}
+
+impl Visit for EventVisitor {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        match field.name() {
+            "message" => self.message.push_str(value),
+            name => {
+                let _ = write!(self.fields, " {}={}", name, value);
+            }
+        }
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        match field.name() {
+            "message" => {
+                let _ = write!(self.message, "{:?}", value);
+            }
+            name => {
+                let _ = write!(self.fields, " {}={:?}", name, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use tracing_subscriber::layer::SubscriberExt;
+    use tracing_subscriber::Registry;
+
+    use super::*;
+    use crate::testing::CaptureWriter;
+
+    #[test]
+    fn test_layer_writes_events() {
+        let buffer = CaptureWriter::new();
+        let layer = SpinnerLayer::new().output_stream(SpinnerStream::custom(buffer.clone()));
+        let subscriber = Registry::default().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || {
+            tracing::warn!(path = "/tmp", retries = 3, "disk almost full");
+            tracing::info!("done");
+        });
+
+        assert_eq!(
+            buffer.contents(),
+            "WARN  disk almost full path=/tmp retries=3\nINFO  done\n"
+        );
+    }
+
+    #[test]
+    fn test_layer_colors_level_when_forced() {
+        let buffer = CaptureWriter::new();
+        let layer = SpinnerLayer::new()
+            .color_policy(ColorPolicy::Always)
+            .output_stream(SpinnerStream::custom(buffer.clone()));
+        let subscriber = Registry::default().with(layer);
+
+        tracing::subscriber::with_default(subscriber, || tracing::error!("failed"));
+
+        assert_eq!(buffer.contents(), "\x1B[31mERROR\x1B[0m failed\n");
+    }
+}